@@ -1,8 +1,17 @@
 // CAL Token Contract
-// This is a simplified implementation of the CAL token using Solana's SPL Token program
+// This is a simplified implementation of the CAL token using Solana's SPL Token program.
+// All token CPIs go through the token interface, so CAL can be issued under either the
+// classic SPL Token program or Token-2022 (with transfer-fee, metadata-pointer and token
+// metadata extensions).
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::{self, spl_token_2022};
+use anchor_spl::token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::{
+    Field, TokenMetadata as InterfaceTokenMetadata,
+};
+use anchor_spl::token_2022_extensions::{metadata_pointer, token_metadata, transfer_fee};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
 use solana_program::{program_option::COption, pubkey::Pubkey};
 
 declare_id!("CalctraTokenProgramID111111111111111111111111111");
@@ -12,48 +21,317 @@ pub mod cal_token {
     use super::*;
 
     // Initialize a new CAL token mint
-    pub fn initialize_mint(ctx: Context<InitializeMint>, decimals: u8) -> Result<()> {
-        // Create a new mint with the specified decimals
-        // The mint authority will be set to the program authority
-        let mint_authority = ctx.accounts.authority.key();
+    pub fn initialize_mint(
+        ctx: Context<InitializeMint>,
+        decimals: u8,
+        supply_cap: u64,
+        emission_schedule: EmissionSchedule,
+        metadata: MetadataArgs,
+        transfer_fee: Option<TransferFeeArgs>,
+    ) -> Result<()> {
+        require!(
+            emission_schedule.period_seconds > 0,
+            ErrorCode::InvalidEmissionSchedule
+        );
+        metadata.validate()?;
+        if let Some(fee) = &transfer_fee {
+            require!(
+                fee.basis_points <= TransferFeeArgs::MAX_BASIS_POINTS,
+                ErrorCode::InvalidTransferFee
+            );
+        }
+
+        // Publish name, symbol and logo so wallets and explorers can display CAL
+        let token_metadata = &mut ctx.accounts.metadata;
+        token_metadata.mint = ctx.accounts.mint.key();
+        token_metadata.update_authority = ctx.accounts.authority.key();
+        token_metadata.name = metadata.name.clone();
+        token_metadata.symbol = metadata.symbol.clone();
+        token_metadata.uri = metadata.uri.clone();
+        token_metadata.description = metadata.description.clone();
+
+        // Mint and freeze authorities are held by the mint config PDA, so no
+        // single wallet key can mint or freeze outside the program rules
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.mint = ctx.accounts.mint.key();
+        mint_config.admin = ctx.accounts.authority.key();
+        mint_config.bump = ctx.bumps.mint_config;
+        mint_config.supply_cap = supply_cap;
+        mint_config.total_minted = 0;
+        mint_config.emission_schedule = emission_schedule;
+        mint_config.minters = Vec::new();
+
+        let mint_authority = mint_config.key();
+        let mint_key = mint_config.mint;
+        let bump = mint_config.bump;
+        let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
         let token_program = ctx.accounts.token_program.to_account_info();
         let mint = ctx.accounts.mint.to_account_info();
         let rent = ctx.accounts.rent.to_account_info();
 
+        // Token-2022 extensions must be initialized before the mint itself. The
+        // mint account has to be allocated by the caller with room for them.
+        // Wallets read the metadata pointer expecting the token metadata interface
+        // layout, so it points at the mint, which carries that extension itself.
+        let is_token_2022 = ctx.accounts.token_program.key() == token_2022::ID;
+        if is_token_2022 {
+            metadata_pointer::metadata_pointer_initialize(
+                CpiContext::new(
+                    token_program.clone(),
+                    metadata_pointer::MetadataPointerInitialize {
+                        token_program_id: token_program.clone(),
+                        mint: mint.clone(),
+                    },
+                ),
+                Some(mint_authority),
+                Some(mint_key),
+            )?;
+
+            if let Some(fee) = transfer_fee {
+                transfer_fee::transfer_fee_initialize(
+                    CpiContext::new(
+                        token_program.clone(),
+                        transfer_fee::TransferFeeInitialize {
+                            token_program_id: token_program.clone(),
+                            mint: mint.clone(),
+                        },
+                    ),
+                    Some(&mint_authority),
+                    Some(&mint_authority),
+                    fee.basis_points,
+                    fee.maximum_fee,
+                )?;
+            }
+        } else {
+            require!(transfer_fee.is_none(), ErrorCode::TransferFeeRequiresToken2022);
+        }
+
         // Initialize the mint account
-        token::initialize_mint(
+        token_interface::initialize_mint(
             CpiContext::new(
-                token_program,
-                token::InitializeMint {
-                    mint,
+                token_program.clone(),
+                token_interface::InitializeMint {
+                    mint: mint.clone(),
                     rent,
                 },
             ),
             decimals,
-            mint_authority,
-            Some(mint_authority),
+            &mint_authority,
+            Some(&mint_authority),
         )?;
 
+        if is_token_2022 {
+            // The metadata extension grows the mint, so top it up to stay rent exempt
+            let metadata_len = InterfaceTokenMetadata {
+                update_authority: OptionalNonZeroPubkey::try_from(Some(mint_authority))?,
+                mint: mint_key,
+                name: metadata.name.clone(),
+                symbol: metadata.symbol.clone(),
+                uri: metadata.uri.clone(),
+                additional_metadata: vec![(
+                    TokenMetadata::DESCRIPTION_KEY.to_string(),
+                    metadata.description.clone(),
+                )],
+            }
+            .tlv_size_of()?;
+            let required = Rent::get()?.minimum_balance(mint.data_len() + metadata_len);
+            let top_up = required.saturating_sub(mint.lamports());
+            if top_up > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.authority.to_account_info(),
+                            to: mint.clone(),
+                        },
+                    ),
+                    top_up,
+                )?;
+            }
+
+            // The mint config PDA is the update authority, so changes go through update_metadata
+            let authority = ctx.accounts.mint_config.to_account_info();
+            token_metadata::token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_metadata::TokenMetadataInitialize {
+                        token_program_id: token_program.clone(),
+                        mint: mint.clone(),
+                        metadata: mint.clone(),
+                        mint_authority: authority.clone(),
+                        update_authority: authority.clone(),
+                    },
+                    &[seeds],
+                ),
+                metadata.name,
+                metadata.symbol,
+                metadata.uri,
+            )?;
+            token_metadata::token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_metadata::TokenMetadataUpdateField {
+                        token_program_id: token_program,
+                        metadata: mint,
+                        update_authority: authority,
+                    },
+                    &[seeds],
+                ),
+                Field::Key(TokenMetadata::DESCRIPTION_KEY.to_string()),
+                metadata.description,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Update the token metadata
+    pub fn update_metadata(ctx: Context<UpdateMetadata>, metadata: MetadataArgs) -> Result<()> {
+        metadata.validate()?;
+
+        let token_metadata = &mut ctx.accounts.metadata;
+        token_metadata.name = metadata.name.clone();
+        token_metadata.symbol = metadata.symbol.clone();
+        token_metadata.uri = metadata.uri.clone();
+        token_metadata.description = metadata.description.clone();
+
+        // Keep the mint's own metadata extension in sync for Token-2022 mints
+        if ctx.accounts.token_program.key() == token_2022::ID {
+            let mint_config = &ctx.accounts.mint_config;
+            let mint_key = mint_config.mint;
+            let bump = mint_config.bump;
+            let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
+
+            let token_program = ctx.accounts.token_program.to_account_info();
+            let mint = ctx.accounts.mint.to_account_info();
+            let authority = ctx.accounts.mint_config.to_account_info();
+            let fields = [
+                (Field::Name, metadata.name),
+                (Field::Symbol, metadata.symbol),
+                (Field::Uri, metadata.uri),
+                (
+                    Field::Key(TokenMetadata::DESCRIPTION_KEY.to_string()),
+                    metadata.description,
+                ),
+            ];
+            for (field, value) in fields {
+                token_metadata::token_metadata_update_field(
+                    CpiContext::new_with_signer(
+                        token_program.clone(),
+                        token_metadata::TokenMetadataUpdateField {
+                            token_program_id: token_program.clone(),
+                            metadata: mint.clone(),
+                            update_authority: authority.clone(),
+                        },
+                        &[seeds],
+                    ),
+                    field,
+                    value,
+                )?;
+            }
+        }
+
+        msg!("Token metadata updated");
+        Ok(())
+    }
+
+    // Hand the metadata update authority to a new key
+    pub fn set_metadata_update_authority(
+        ctx: Context<UpdateMetadata>,
+        new_update_authority: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.metadata.update_authority = new_update_authority;
+
+        msg!("Metadata update authority changed to {}", new_update_authority);
+        Ok(())
+    }
+
+    // Grant the minter role to a key
+    pub fn add_minter(ctx: Context<UpdateMinters>, minter: Pubkey) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+
+        require!(
+            !mint_config.minters.contains(&minter),
+            ErrorCode::MinterAlreadyExists
+        );
+        require!(
+            mint_config.minters.len() < MintConfig::MAX_MINTERS,
+            ErrorCode::TooManyMinters
+        );
+
+        mint_config.minters.push(minter);
+
+        msg!("Minter added: {}", minter);
+        Ok(())
+    }
+
+    // Revoke the minter role from a key
+    pub fn remove_minter(ctx: Context<UpdateMinters>, minter: Pubkey) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+
+        let position = mint_config
+            .minters
+            .iter()
+            .position(|key| *key == minter)
+            .ok_or(ErrorCode::MinterNotFound)?;
+        mint_config.minters.remove(position);
+
+        msg!("Minter removed: {}", minter);
+        Ok(())
+    }
+
+    // Hand the admin role to a new key, typically the multisig signer PDA
+    pub fn set_admin(ctx: Context<SetAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.mint_config.admin = new_admin;
+
+        msg!("Mint config admin changed to {}", new_admin);
         Ok(())
     }
 
     // Mint new CAL tokens to a recipient
     pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
-        // Only the mint authority can mint new tokens
+        let mint_config = &mut ctx.accounts.mint_config;
+
+        // Only registered minters can mint new tokens
+        require!(
+            mint_config.minters.contains(&ctx.accounts.minter.key()),
+            ErrorCode::UnauthorizedMinter
+        );
+
+        // Enforce the supply cap and the emission schedule
+        let total_minted = mint_config
+            .total_minted
+            .checked_add(amount)
+            .ok_or(ErrorCode::SupplyCapExceeded)?;
+        require!(
+            total_minted <= mint_config.supply_cap,
+            ErrorCode::SupplyCapExceeded
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            total_minted <= mint_config.emitted_at(now),
+            ErrorCode::EmissionScheduleExceeded
+        );
+        mint_config.total_minted = total_minted;
+
+        let mint_key = mint_config.mint;
+        let bump = mint_config.bump;
+        let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
+
         let token_program = ctx.accounts.token_program.to_account_info();
         let mint = ctx.accounts.mint.to_account_info();
         let to = ctx.accounts.to.to_account_info();
-        let authority = ctx.accounts.authority.to_account_info();
+        let authority = ctx.accounts.mint_config.to_account_info();
 
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 token_program,
-                token::MintTo {
+                token_interface::MintTo {
                     mint,
                     to,
                     authority,
                 },
-                &[],
+                &[seeds],
             ),
             amount,
         )?;
@@ -61,18 +339,72 @@ pub mod cal_token {
         Ok(())
     }
 
+    // Move transfer fees withheld in token accounts into the mint. Fees accrue on the
+    // recipient accounts, so they must be harvested before they can be withdrawn.
+    // Anyone can harvest; the token accounts are passed as remaining accounts.
+    pub fn harvest_transfer_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestTransferFees<'info>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            ErrorCode::NoHarvestSources
+        );
+
+        transfer_fee::harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_fee::HarvestWithheldTokensToMint {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            ctx.remaining_accounts.to_vec(),
+        )?;
+
+        msg!(
+            "Harvested withheld transfer fees from {} accounts",
+            ctx.remaining_accounts.len()
+        );
+        Ok(())
+    }
+
+    // Withdraw transfer fees withheld in the mint to a protocol treasury account
+    pub fn withdraw_transfer_fees(ctx: Context<WithdrawTransferFees>) -> Result<()> {
+        let mint_config = &ctx.accounts.mint_config;
+        let mint_key = mint_config.mint;
+        let bump = mint_config.bump;
+        let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
+
+        transfer_fee::withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_fee::WithdrawWithheldTokensFromMint {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                destination: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.mint_config.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+
+        msg!("Withheld transfer fees withdrawn to treasury");
+        Ok(())
+    }
+
     // Transfer CAL tokens between accounts
     pub fn transfer(ctx: Context<TransferTokens>, amount: u64) -> Result<()> {
-        token::transfer(
+        // transfer_checked is required by Token-2022 mints with a transfer fee
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                token_interface::TransferChecked {
                     from: ctx.accounts.from.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.to.to_account_info(),
                     authority: ctx.accounts.authority.to_account_info(),
                 },
             ),
             amount,
+            ctx.accounts.mint.decimals,
         )?;
 
         Ok(())
@@ -80,10 +412,10 @@ pub mod cal_token {
 
     // Burn CAL tokens
     pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
-        token::burn(
+        token_interface::burn(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                token::Burn {
+                token_interface::Burn {
                     mint: ctx.accounts.mint.to_account_info(),
                     from: ctx.accounts.from.to_account_info(),
                     authority: ctx.accounts.authority.to_account_info(),
@@ -101,21 +433,31 @@ pub mod cal_token {
         authority_type: AuthorityType,
         new_authority: Option<Pubkey>,
     ) -> Result<()> {
+        // The mint authority can only be renounced, never handed to another key
         let auth_type = match authority_type {
-            AuthorityType::MintTokens => spl_token::instruction::AuthorityType::MintTokens,
-            AuthorityType::FreezeAccount => spl_token::instruction::AuthorityType::FreezeAccount,
+            AuthorityType::MintTokens => {
+                require!(new_authority.is_none(), ErrorCode::MintAuthorityLocked);
+                spl_token_2022::instruction::AuthorityType::MintTokens
+            }
+            AuthorityType::FreezeAccount => spl_token_2022::instruction::AuthorityType::FreezeAccount,
         };
 
-        token::set_authority(
-            CpiContext::new(
+        let mint_config = &ctx.accounts.mint_config;
+        let mint_key = mint_config.mint;
+        let bump = mint_config.bump;
+        let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
+
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                token::SetAuthority {
+                token_interface::SetAuthority {
                     account_or_mint: ctx.accounts.mint_account.to_account_info(),
-                    current_authority: ctx.accounts.current_authority.to_account_info(),
+                    current_authority: ctx.accounts.mint_config.to_account_info(),
                 },
+                &[seeds],
             ),
             auth_type,
-            new_authority.as_ref(),
+            new_authority,
         )?;
 
         Ok(())
@@ -123,14 +465,20 @@ pub mod cal_token {
 
     // Freeze token account to prevent transfers
     pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
-        token::freeze_account(
-            CpiContext::new(
+        let mint_config = &ctx.accounts.mint_config;
+        let mint_key = mint_config.mint;
+        let bump = mint_config.bump;
+        let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
+
+        token_interface::freeze_account(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                token::FreezeAccount {
+                token_interface::FreezeAccount {
                     account: ctx.accounts.account.to_account_info(),
                     mint: ctx.accounts.mint.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
+                    authority: ctx.accounts.mint_config.to_account_info(),
                 },
+                &[seeds],
             ),
         )?;
 
@@ -139,14 +487,20 @@ pub mod cal_token {
 
     // Thaw (unfreeze) token account
     pub fn thaw_account(ctx: Context<ThawAccount>) -> Result<()> {
-        token::thaw_account(
-            CpiContext::new(
+        let mint_config = &ctx.accounts.mint_config;
+        let mint_key = mint_config.mint;
+        let bump = mint_config.bump;
+        let seeds: &[&[u8]] = &[b"mint_config", mint_key.as_ref(), &[bump]];
+
+        token_interface::thaw_account(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                token::ThawAccount {
+                token_interface::ThawAccount {
                     account: ctx.accounts.account.to_account_info(),
                     mint: ctx.accounts.mint.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
+                    authority: ctx.accounts.mint_config.to_account_info(),
                 },
+                &[seeds],
             ),
         )?;
 
@@ -154,73 +508,322 @@ pub mod cal_token {
     }
 }
 
+// Program rules for minting, held by the PDA that owns the mint and freeze authorities
+#[account]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub bump: u8,
+    pub supply_cap: u64,
+    pub total_minted: u64,
+    pub emission_schedule: EmissionSchedule,
+    pub minters: Vec<Pubkey>,
+}
+
+impl MintConfig {
+    pub const MAX_MINTERS: usize = 10;
+    pub const SPACE: usize = 8 + 32 + 32 + 1 + 8 + 8 + EmissionSchedule::SPACE + 4 + 32 * Self::MAX_MINTERS;
+
+    // Total amount the schedule allows to have been minted at `now`
+    pub fn emitted_at(&self, now: i64) -> u64 {
+        let schedule = &self.emission_schedule;
+        if now < schedule.start_ts {
+            return 0;
+        }
+        let periods = ((now - schedule.start_ts) / schedule.period_seconds) as u64 + 1;
+        schedule
+            .initial_amount
+            .saturating_add(periods.saturating_mul(schedule.amount_per_period))
+            .min(self.supply_cap)
+    }
+}
+
+// Calctra token metadata, stored in a PDA derived from the mint. Token-2022 mints
+// also carry it in their own metadata extension, which is what wallets read.
+#[account]
+pub struct TokenMetadata {
+    pub mint: Pubkey,
+    pub update_authority: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub description: String,
+}
+
+impl TokenMetadata {
+    pub const MAX_NAME_LENGTH: usize = 32;
+    pub const MAX_SYMBOL_LENGTH: usize = 10;
+    pub const MAX_URI_LENGTH: usize = 200;
+    pub const MAX_DESCRIPTION_LENGTH: usize = 200;
+    // Additional metadata key the description is stored under in the mint extension
+    pub const DESCRIPTION_KEY: &'static str = "description";
+    pub const SPACE: usize = 8
+        + 32
+        + 32
+        + 4 + Self::MAX_NAME_LENGTH
+        + 4 + Self::MAX_SYMBOL_LENGTH
+        + 4 + Self::MAX_URI_LENGTH
+        + 4 + Self::MAX_DESCRIPTION_LENGTH;
+}
+
+// Token-2022 transfer fee, in basis points of each transfer capped at `maximum_fee`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferFeeArgs {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+impl TransferFeeArgs {
+    pub const MAX_BASIS_POINTS: u16 = 10_000;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub description: String,
+}
+
+impl MetadataArgs {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.name.len() <= TokenMetadata::MAX_NAME_LENGTH,
+            ErrorCode::NameTooLong
+        );
+        require!(
+            self.symbol.len() <= TokenMetadata::MAX_SYMBOL_LENGTH,
+            ErrorCode::SymbolTooLong
+        );
+        require!(
+            self.uri.len() <= TokenMetadata::MAX_URI_LENGTH,
+            ErrorCode::UriTooLong
+        );
+        require!(
+            self.description.len() <= TokenMetadata::MAX_DESCRIPTION_LENGTH,
+            ErrorCode::DescriptionTooLong
+        );
+        Ok(())
+    }
+}
+
+// Linear release of new supply: `initial_amount` at `start_ts`, then
+// `amount_per_period` for every `period_seconds` that has started since
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EmissionSchedule {
+    pub start_ts: i64,
+    pub period_seconds: i64,
+    pub initial_amount: u64,
+    pub amount_per_period: u64,
+}
+
+impl EmissionSchedule {
+    pub const SPACE: usize = 8 + 8 + 8 + 8;
+}
+
 #[derive(Accounts)]
 pub struct InitializeMint<'info> {
+    /// CHECK: Uninitialized mint allocated by the caller, owned by the token program
+    #[account(mut, owner = token_program.key())]
+    pub mint: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = MintConfig::SPACE,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = TokenMetadata::SPACE,
+        seeds = [b"metadata", mint.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, TokenMetadata>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut, has_one = update_authority, has_one = mint)]
+    pub metadata: Account<'info, TokenMetadata>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        has_one = mint,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    pub update_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMinters<'info> {
+    #[account(mut, has_one = admin)]
+    pub mint_config: Account<'info, MintConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAdmin<'info> {
+    #[account(mut, has_one = admin)]
+    pub mint_config: Account<'info, MintConfig>,
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct MintTokens<'info> {
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub minter: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestTransferFees<'info> {
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTransferFees<'info> {
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        has_one = mint,
+        has_one = admin,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct TransferTokens<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
+    pub from: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+    pub to: InterfaceAccount<'info, TokenAccount>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct BurnTokens<'info> {
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
+    pub from: InterfaceAccount<'info, TokenAccount>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct SetAuthority<'info> {
     #[account(mut)]
-    pub mint_account: Account<'info, Mint>,
-    pub current_authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        has_one = admin,
+        constraint = mint_config.mint == mint_account.key(),
+        seeds = [b"mint_config", mint_account.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct FreezeAccount<'info> {
     #[account(mut)]
-    pub account: Account<'info, TokenAccount>,
-    pub mint: Account<'info, Mint>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        has_one = mint,
+        has_one = admin,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct ThawAccount<'info> {
     #[account(mut)]
-    pub account: Account<'info, TokenAccount>,
-    pub mint: Account<'info, Mint>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        has_one = mint,
+        has_one = admin,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub enum AuthorityType {
     MintTokens,
     FreezeAccount,
-} 
\ No newline at end of file
+} 
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    #[msg("Signer is not a registered minter")]
+    UnauthorizedMinter,
+    #[msg("Minter is already registered")]
+    MinterAlreadyExists,
+    #[msg("Minter is not registered")]
+    MinterNotFound,
+    #[msg("Too many minters")]
+    TooManyMinters,
+    #[msg("Mint would exceed the supply cap")]
+    SupplyCapExceeded,
+    #[msg("Mint would exceed the emission schedule")]
+    EmissionScheduleExceeded,
+    #[msg("Mint authority can only be renounced")]
+    MintAuthorityLocked,
+    #[msg("Token name is too long")]
+    NameTooLong,
+    #[msg("Token symbol is too long")]
+    SymbolTooLong,
+    #[msg("Token URI is too long")]
+    UriTooLong,
+    #[msg("Token description is too long")]
+    DescriptionTooLong,
+    #[msg("Transfer fee basis points out of range")]
+    InvalidTransferFee,
+    #[msg("Transfer fees require the Token-2022 program")]
+    TransferFeeRequiresToken2022,
+    #[msg("No token accounts to harvest withheld fees from")]
+    NoHarvestSources,
+}
@@ -0,0 +1,358 @@
+// CAL Merkle Distributor Contract
+// Distributes onboarding credits in CAL to a list of recipients committed to by a merkle root
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("CalctraMerkleDistributorID11111111111111111111");
+
+// Domain separation prefixes so a leaf can never be passed off as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+#[program]
+pub mod merkle_distributor {
+    use super::*;
+
+    // Create a new distributor and fund its vault with the full airdrop amount
+    pub fn new_distributor(
+        ctx: Context<NewDistributor>,
+        root: [u8; 32],
+        max_total_claim: u64,
+        max_num_nodes: u64,
+        clawback_start_ts: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(clawback_start_ts > now, ErrorCode::InvalidClawbackDeadline);
+        require!(max_num_nodes > 0, ErrorCode::InvalidNodeCount);
+        require!(
+            max_num_nodes <= MerkleDistributor::MAX_NUM_NODES,
+            ErrorCode::InvalidNodeCount
+        );
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.base = ctx.accounts.base.key();
+        distributor.bump = ctx.bumps.distributor;
+        distributor.admin = ctx.accounts.admin.key();
+        distributor.mint = ctx.accounts.mint.key();
+        distributor.vault = ctx.accounts.vault.key();
+        distributor.root = root;
+        distributor.max_total_claim = max_total_claim;
+        distributor.max_num_nodes = max_num_nodes;
+        distributor.total_amount_claimed = 0;
+        distributor.num_nodes_claimed = 0;
+        distributor.clawback_start_ts = clawback_start_ts;
+        distributor.clawed_back = false;
+        distributor.claimed_bitmap = vec![0; MerkleDistributor::bitmap_len(max_num_nodes)];
+
+        // Move the total airdrop amount into the distributor vault
+        let deposit = gross_deposit(&ctx.accounts.mint.to_account_info(), max_total_claim)?;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.admin_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            ),
+            deposit,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.amount >= max_total_claim,
+            ErrorCode::VaultUnderfunded
+        );
+
+        msg!(
+            "Distributor created for {} recipients totalling {}",
+            max_num_nodes,
+            max_total_claim
+        );
+        Ok(())
+    }
+
+    // Claim an allocation by proving membership in the merkle tree
+    pub fn claim(
+        ctx: Context<Claim>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        let claimant = ctx.accounts.claimant.key();
+
+        require!(!distributor.clawed_back, ErrorCode::ClawbackCompleted);
+        require!(
+            Clock::get()?.unix_timestamp < distributor.clawback_start_ts,
+            ErrorCode::ClaimWindowClosed
+        );
+        require!(index < distributor.max_num_nodes, ErrorCode::InvalidIndex);
+        require!(!distributor.is_claimed(index), ErrorCode::AlreadyClaimed);
+
+        // Verify the leaf against the posted root
+        let leaf = keccak::hashv(&[
+            LEAF_PREFIX,
+            &index.to_le_bytes(),
+            claimant.as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .0;
+        require!(
+            verify_proof(&proof, distributor.root, leaf),
+            ErrorCode::InvalidProof
+        );
+
+        // Mark the leaf as claimed and update totals
+        distributor.set_claimed(index);
+        distributor.total_amount_claimed = distributor
+            .total_amount_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::ExceededMaxClaim)?;
+        require!(
+            distributor.total_amount_claimed <= distributor.max_total_claim,
+            ErrorCode::ExceededMaxClaim
+        );
+        distributor.num_nodes_claimed += 1;
+        require!(
+            distributor.num_nodes_claimed <= distributor.max_num_nodes,
+            ErrorCode::ExceededNumNodes
+        );
+
+        // Pay out from the vault, signed by the distributor PDA
+        let base = distributor.base;
+        let bump = distributor.bump;
+        let seeds: &[&[u8]] = &[b"distributor", base.as_ref(), &[bump]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.claimant_token_account.to_account_info(),
+                    authority: ctx.accounts.distributor.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(ClaimedEvent {
+            distributor: ctx.accounts.distributor.key(),
+            index,
+            claimant,
+            amount,
+        });
+
+        msg!("Claimed {} CAL for index {}", amount, index);
+        Ok(())
+    }
+
+    // Return all unclaimed funds to the admin once the claim deadline has passed
+    pub fn clawback(ctx: Context<Clawback>) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+
+        require!(!distributor.clawed_back, ErrorCode::ClawbackCompleted);
+        require!(
+            Clock::get()?.unix_timestamp >= distributor.clawback_start_ts,
+            ErrorCode::ClawbackBeforeDeadline
+        );
+
+        let amount = ctx.accounts.vault.amount;
+        let base = distributor.base;
+        let bump = distributor.bump;
+        let seeds: &[&[u8]] = &[b"distributor", base.as_ref(), &[bump]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.clawback_receiver.to_account_info(),
+                    authority: ctx.accounts.distributor.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        ctx.accounts.distributor.clawed_back = true;
+
+        msg!("Clawed back {} unclaimed CAL", amount);
+        Ok(())
+    }
+}
+
+// Verify a merkle proof, hashing each pair of siblings in sorted order
+fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof.iter() {
+        computed = if computed <= *node {
+            keccak::hashv(&[NODE_PREFIX, &computed, node]).0
+        } else {
+            keccak::hashv(&[NODE_PREFIX, node, &computed]).0
+        };
+    }
+    computed == root
+}
+
+// Amount to send so that `amount` arrives after the mint's Token-2022 transfer fee, if any
+fn gross_deposit(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .get_epoch_fee(Clock::get()?.epoch)
+            .calculate_inverse_fee(amount)
+            .ok_or(ErrorCode::VaultUnderfunded)?,
+        Err(_) => 0,
+    };
+    amount
+        .checked_add(fee)
+        .ok_or_else(|| error!(ErrorCode::VaultUnderfunded))
+}
+
+#[account]
+pub struct MerkleDistributor {
+    pub base: Pubkey,
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub root: [u8; 32],
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+    pub total_amount_claimed: u64,
+    pub num_nodes_claimed: u64,
+    pub clawback_start_ts: i64,
+    pub clawed_back: bool,
+    pub claimed_bitmap: Vec<u8>,
+}
+
+impl MerkleDistributor {
+    // Keeps the account within the 10KiB limit for accounts created through CPI
+    pub const MAX_NUM_NODES: u64 = 80_000;
+
+    pub fn bitmap_len(max_num_nodes: u64) -> usize {
+        ((max_num_nodes + 7) / 8) as usize
+    }
+
+    pub fn space(max_num_nodes: u64) -> usize {
+        8 + 32 + 1 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 4 + Self::bitmap_len(max_num_nodes)
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        let byte = self.claimed_bitmap[(index / 8) as usize];
+        byte & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u64) {
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+#[event]
+pub struct ClaimedEvent {
+    pub distributor: Pubkey,
+    pub index: u64,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], max_total_claim: u64, max_num_nodes: u64)]
+pub struct NewDistributor<'info> {
+    pub base: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = MerkleDistributor::space(max_num_nodes),
+        seeds = [b"distributor", base.key().as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = distributor,
+        token::token_program = token_program,
+        seeds = [b"vault", distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = admin_token_account.mint == mint.key())]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut, has_one = vault, has_one = mint)]
+    pub distributor: Account<'info, MerkleDistributor>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = claimant_token_account.mint == distributor.mint,
+        constraint = claimant_token_account.owner == claimant.key()
+    )]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub claimant: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    #[account(mut, has_one = vault, has_one = mint, has_one = admin)]
+    pub distributor: Account<'info, MerkleDistributor>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = clawback_receiver.mint == distributor.mint)]
+    pub clawback_receiver: InterfaceAccount<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Clawback deadline must be in the future")]
+    InvalidClawbackDeadline,
+    #[msg("Invalid number of recipients")]
+    InvalidNodeCount,
+    #[msg("Claim index is out of range")]
+    InvalidIndex,
+    #[msg("Allocation has already been claimed")]
+    AlreadyClaimed,
+    #[msg("Invalid merkle proof")]
+    InvalidProof,
+    #[msg("Claims exceed the distributor total")]
+    ExceededMaxClaim,
+    #[msg("Claims exceed the number of recipients")]
+    ExceededNumNodes,
+    #[msg("Claim window has closed")]
+    ClaimWindowClosed,
+    #[msg("Clawback deadline has not passed")]
+    ClawbackBeforeDeadline,
+    #[msg("Unclaimed funds have already been clawed back")]
+    ClawbackCompleted,
+    #[msg("Vault holds less than the distributor total")]
+    VaultUnderfunded,
+}
@@ -0,0 +1,316 @@
+// Calctra Multisig Contract
+// M-of-N multisig that proposes, approves and executes arbitrary instructions.
+// The multisig signer PDA is meant to hold the CAL mint config admin and the
+// resource matching system authority, so no single key can act alone.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+declare_id!("CalctraMultisigProgramID1111111111111111111111");
+
+#[program]
+pub mod multisig {
+    use super::*;
+
+    // Create a new multisig with the given owners and approval threshold
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+        nonce: u8,
+    ) -> Result<()> {
+        assert_unique_owners(&owners)?;
+        require!(
+            threshold > 0 && threshold <= owners.len() as u64,
+            ErrorCode::InvalidThreshold
+        );
+        require!(
+            owners.len() <= Multisig::MAX_OWNERS,
+            ErrorCode::TooManyOwners
+        );
+
+        // Make sure the nonce derives the signer PDA
+        Pubkey::create_program_address(
+            &[ctx.accounts.multisig.key().as_ref(), &[nonce]],
+            ctx.program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidMultisigNonce)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.nonce = nonce;
+        multisig.owner_set_seqno = 0;
+
+        msg!("Multisig created with threshold {}", threshold);
+        Ok(())
+    }
+
+    // Propose a new instruction to be executed by the multisig signer
+    pub fn create_transaction(
+        ctx: Context<CreateTransaction>,
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let owner_index = multisig
+            .owner_index(&ctx.accounts.proposer.key())
+            .ok_or(ErrorCode::InvalidOwner)?;
+
+        // The proposer implicitly approves their own transaction
+        let mut signers = vec![false; multisig.owners.len()];
+        signers[owner_index] = true;
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.multisig = multisig.key();
+        transaction.program_id = program_id;
+        transaction.accounts = accounts;
+        transaction.data = data;
+        transaction.signers = signers;
+        transaction.did_execute = false;
+        transaction.owner_set_seqno = multisig.owner_set_seqno;
+
+        msg!("Transaction proposed by {}", ctx.accounts.proposer.key());
+        Ok(())
+    }
+
+    // Approve a proposed transaction
+    pub fn approve(ctx: Context<Approve>) -> Result<()> {
+        let owner_index = ctx
+            .accounts
+            .multisig
+            .owner_index(&ctx.accounts.owner.key())
+            .ok_or(ErrorCode::InvalidOwner)?;
+
+        let transaction = &mut ctx.accounts.transaction;
+        require!(!transaction.did_execute, ErrorCode::AlreadyExecuted);
+        transaction.signers[owner_index] = true;
+
+        msg!("Transaction approved by {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    // Replace the owner set. Only callable by the multisig itself via execute_transaction
+    pub fn set_owners(ctx: Context<Auth>, owners: Vec<Pubkey>) -> Result<()> {
+        assert_unique_owners(&owners)?;
+        require!(!owners.is_empty(), ErrorCode::InvalidOwnersLen);
+        require!(
+            owners.len() <= Multisig::MAX_OWNERS,
+            ErrorCode::TooManyOwners
+        );
+
+        let multisig = &mut ctx.accounts.multisig;
+        if (owners.len() as u64) < multisig.threshold {
+            multisig.threshold = owners.len() as u64;
+        }
+        multisig.owners = owners;
+
+        // Invalidate all pending transactions approved by the previous owner set
+        multisig.owner_set_seqno += 1;
+
+        Ok(())
+    }
+
+    // Change the approval threshold. Only callable by the multisig itself via execute_transaction
+    pub fn change_threshold(ctx: Context<Auth>, threshold: u64) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        require!(
+            threshold > 0 && threshold <= multisig.owners.len() as u64,
+            ErrorCode::InvalidThreshold
+        );
+        multisig.threshold = threshold;
+        Ok(())
+    }
+
+    // Execute a transaction once enough owners have approved it
+    pub fn execute_transaction(ctx: Context<ExecuteTransaction>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let transaction = &ctx.accounts.transaction;
+
+        require!(!transaction.did_execute, ErrorCode::AlreadyExecuted);
+        require!(
+            multisig.owner_index(&ctx.accounts.executor.key()).is_some(),
+            ErrorCode::InvalidOwner
+        );
+
+        let approvals = transaction.signers.iter().filter(|&&signed| signed).count() as u64;
+        require!(approvals >= multisig.threshold, ErrorCode::NotEnoughSigners);
+
+        // Build the instruction, marking the multisig signer PDA as a signer
+        let multisig_signer = ctx.accounts.multisig_signer.key();
+        let mut instruction: Instruction = (&**transaction).into();
+        instruction.accounts = instruction
+            .accounts
+            .iter()
+            .map(|account| {
+                let mut account = account.clone();
+                if account.pubkey == multisig_signer {
+                    account.is_signer = true;
+                }
+                account
+            })
+            .collect();
+
+        let multisig_key = multisig.key();
+        let nonce = multisig.nonce;
+
+        // Mark the transaction executed before the CPI so it cannot be replayed through reentry
+        ctx.accounts.transaction.did_execute = true;
+        ctx.accounts.transaction.exit(ctx.program_id)?;
+
+        let seeds: &[&[u8]] = &[multisig_key.as_ref(), &[nonce]];
+        invoke_signed(&instruction, ctx.remaining_accounts, &[seeds])?;
+
+        msg!("Transaction executed with {} approvals", approvals);
+        Ok(())
+    }
+}
+
+// Reject owner lists that contain the same key twice
+fn assert_unique_owners(owners: &[Pubkey]) -> Result<()> {
+    for (i, owner) in owners.iter().enumerate() {
+        require!(
+            !owners.iter().skip(i + 1).any(|other| other == owner),
+            ErrorCode::UniqueOwners
+        );
+    }
+    Ok(())
+}
+
+#[account]
+pub struct Multisig {
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub nonce: u8,
+    pub owner_set_seqno: u32,
+}
+
+impl Multisig {
+    pub const MAX_OWNERS: usize = 10;
+    pub const SPACE: usize = 8 + 4 + 32 * Self::MAX_OWNERS + 8 + 1 + 4;
+
+    pub fn owner_index(&self, key: &Pubkey) -> Option<usize> {
+        self.owners.iter().position(|owner| owner == key)
+    }
+}
+
+#[account]
+pub struct Transaction {
+    pub multisig: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    pub signers: Vec<bool>,
+    pub did_execute: bool,
+    pub owner_set_seqno: u32,
+}
+
+impl From<&Transaction> for Instruction {
+    fn from(tx: &Transaction) -> Instruction {
+        Instruction {
+            program_id: tx.program_id,
+            accounts: tx.accounts.iter().map(Into::into).collect(),
+            data: tx.data.clone(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl From<&TransactionAccount> for AccountMeta {
+    fn from(account: &TransactionAccount) -> AccountMeta {
+        match account.is_writable {
+            false => AccountMeta::new_readonly(account.pubkey, account.is_signer),
+            true => AccountMeta::new(account.pubkey, account.is_signer),
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(init, payer = payer, space = Multisig::SPACE)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct CreateTransaction<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + 32 + 32 + 4 + accounts.len() * (32 + 1 + 1) + 4 + data.len()
+            + 4 + multisig.owners.len() + 1 + 4
+    )]
+    pub transaction: Account<'info, Transaction>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Approve<'info> {
+    #[account(constraint = multisig.owner_set_seqno == transaction.owner_set_seqno @ ErrorCode::StaleOwnerSet)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(mut, has_one = multisig)]
+    pub transaction: Account<'info, Transaction>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Auth<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        seeds = [multisig.key().as_ref()],
+        bump = multisig.nonce
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTransaction<'info> {
+    #[account(constraint = multisig.owner_set_seqno == transaction.owner_set_seqno @ ErrorCode::StaleOwnerSet)]
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: PDA signer of the multisig, verified by its seeds
+    #[account(
+        seeds = [multisig.key().as_ref()],
+        bump = multisig.nonce
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+    #[account(mut, has_one = multisig)]
+    pub transaction: Account<'info, Transaction>,
+    pub executor: Signer<'info>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("The given owner is not part of this multisig")]
+    InvalidOwner,
+    #[msg("Owners length must be non zero")]
+    InvalidOwnersLen,
+    #[msg("Too many owners")]
+    TooManyOwners,
+    #[msg("Owners must be unique")]
+    UniqueOwners,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Nonce does not derive the multisig signer")]
+    InvalidMultisigNonce,
+    #[msg("Not enough owners signed this transaction")]
+    NotEnoughSigners,
+    #[msg("The given transaction has already been executed")]
+    AlreadyExecuted,
+    #[msg("Owner set has changed since the transaction was created")]
+    StaleOwnerSet,
+}
@@ -1,12 +1,52 @@
 // Resource Matching System Contract
-// This contract handles the core resource matching functionality of the Calctra platform
+// This contract handles the core resource matching functionality of the Calctra platform.
+// Resource and job descriptors use the shared taxonomy from calctra_contracts::taxonomy
+// and are stored in their on-chain encodings.
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TokenAccount, Transfer};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+use calctra_contracts::attestation::{
+    preceding_ed25519_signer, ResultAttestation, MAX_WORKER_KEYS,
+};
+use calctra_contracts::groth16::{
+    computation_public_inputs, Groth16Proof, Groth16VerifyingKey, COMPUTATION_PUBLIC_INPUTS,
+    G1_LEN, G2_LEN, MAX_PUBLIC_INPUTS,
+};
+use calctra_contracts::offer::ProviderOffer;
+use calctra_contracts::resource_vector::{Dimension, ResourceVector, MAX_DIMENSIONS};
+use calctra_contracts::taxonomy::{
+    AcceleratorModel, CapabilitySet, CpuArchitecture, JobCategory, Locality, RegionCode,
+    RegionScope, TeePlatform, TEE_MEASUREMENT_LEN,
+};
 use std::collections::BTreeMap;
 
 declare_id!("CalctraResourceMatchingID1111111111111111111111");
 
+// Verifying key points: one per public input plus the constant term
+const MAX_IC_POINTS: usize = MAX_PUBLIC_INPUTS + 1;
+
+// Pairs per batch. A pair passes up to four accounts (request, resource, benchmark and
+// TEE attestation), so a full batch plus the system state, matcher, fee payer and program
+// stays well inside the account lock limit of a transaction and leaves compute headroom
+// for loading and writing back every account.
+pub const MAX_BATCH_PAIRS: usize = 8;
+
+// Accounts a transaction can lock
+const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+const _: () = assert!(4 + 4 * MAX_BATCH_PAIRS <= MAX_TX_ACCOUNT_LOCKS);
+
+// Requests plus resources in a stable matching, each passed with its preferences
+pub const MAX_STABLE_PARTICIPANTS: usize = 16;
+
+// Entries in a request or resource preference list
+pub const MAX_PREFERENCES: usize = 8;
+
+// Seconds a provider has to accept a match offered by the system authority
+pub const MATCH_OFFER_TIMEOUT: i64 = 10 * 60;
+
 #[program]
 pub mod resource_matching {
     use super::*;
@@ -21,15 +61,31 @@ pub mod resource_matching {
         Ok(())
     }
 
+    // Hand the system authority to a new key, typically the multisig signer PDA
+    pub fn set_authority(ctx: Context<SetSystemAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.system_state.authority = new_authority;
+
+        msg!("System authority changed to {}", new_authority);
+        Ok(())
+    }
+
     // Register a new computational resource
     pub fn register_resource(
         ctx: Context<RegisterResource>,
-        resource_type: String,
-        computation_power: u64,
-        available_memory: u64,
-        location: String,
+        accelerator: u16,
+        cpu_architecture: u8,
+        capacity: [u64; MAX_DIMENSIONS],
+        capabilities: u128,
+        region: [u8; 2],
+        zone: u8,
         price_per_unit: u64,
     ) -> Result<()> {
+        let accelerator = parse_accelerator(accelerator)?;
+        let cpu_architecture = parse_cpu_architecture(cpu_architecture)?;
+        let region = parse_region(region)?;
+        // Benchmark figures come from verifier attestations, never from the provider
+        let capacity = parse_resource_vector(capacity)?.consumable().0;
+
         let system_state = &mut ctx.accounts.system_state;
         let resource_account = &mut ctx.accounts.resource_account;
         let provider = &ctx.accounts.provider;
@@ -37,14 +93,25 @@ pub mod resource_matching {
         // Create a new resource entry
         resource_account.resource_id = system_state.resource_count;
         resource_account.provider = provider.key();
-        resource_account.resource_type = resource_type;
-        resource_account.computation_power = computation_power;
-        resource_account.available_memory = available_memory;
-        resource_account.location = location;
+        resource_account.accelerator = accelerator.code();
+        resource_account.cpu_architecture = cpu_architecture.code();
+        resource_account.capacity = capacity;
+        resource_account.allocated = [0; MAX_DIMENSIONS];
+        resource_account.capabilities = capabilities;
+        resource_account.region = region.code();
+        resource_account.zone = zone;
         resource_account.price_per_unit = price_per_unit;
         resource_account.is_active = true;
         resource_account.reputation_score = 0;
         resource_account.total_usage_time = 0;
+        resource_account.active_matches = 0;
+        resource_account.worker_keys = Vec::new();
+        resource_account.encryption_key = [0; 32];
+        resource_account.encryption_key_epoch = 0;
+        resource_account.previous_encryption_key = [0; 32];
+        resource_account.min_offer_nonce = 0;
+        resource_account.offers_accepted = 0;
+        resource_account.offers_declined = 0;
 
         // Update system state
         system_state.resource_count += 1;
@@ -53,36 +120,267 @@ pub mod resource_matching {
         Ok(())
     }
 
+    // Update the offer of a registered resource
+    pub fn update_resource(
+        ctx: Context<UpdateResource>,
+        capacity: Option<[u64; MAX_DIMENSIONS]>,
+        capabilities: Option<u128>,
+        price_per_unit: Option<u64>,
+    ) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        if let Some(capacity) = capacity {
+            // Capacity cannot shrink below what running jobs have reserved
+            let capacity = parse_resource_vector(capacity)?.consumable().0;
+            require!(
+                ResourceVector(capacity)
+                    .covers(&ResourceVector(resource_account.allocated))
+                    .is_ok(),
+                ErrorCode::CapacityBelowAllocation
+            );
+            resource_account.capacity = capacity;
+        }
+        if let Some(capabilities) = capabilities {
+            resource_account.capabilities = capabilities;
+        }
+        if let Some(price_per_unit) = price_per_unit {
+            resource_account.price_per_unit = price_per_unit;
+        }
+
+        msg!("Resource {} updated", resource_account.resource_id);
+        Ok(())
+    }
+
+    // Enroll a worker key whose signatures are accepted as result attestations
+    pub fn add_worker_key(ctx: Context<UpdateResource>, worker_key: Pubkey) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        require!(
+            !resource_account.worker_keys.contains(&worker_key),
+            ErrorCode::WorkerKeyAlreadyEnrolled
+        );
+        require!(
+            resource_account.worker_keys.len() < MAX_WORKER_KEYS,
+            ErrorCode::TooManyWorkerKeys
+        );
+        resource_account.worker_keys.push(worker_key);
+
+        msg!("Worker key {} enrolled for resource {}", worker_key, resource_account.resource_id);
+        Ok(())
+    }
+
+    // Revoke a worker key. Attestations it signs are rejected from then on.
+    pub fn remove_worker_key(ctx: Context<UpdateResource>, worker_key: Pubkey) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        let index = resource_account
+            .worker_keys
+            .iter()
+            .position(|key| *key == worker_key)
+            .ok_or(ErrorCode::WorkerKeyNotFound)?;
+        resource_account.worker_keys.remove(index);
+
+        msg!(
+            "Worker key {} removed from resource {}",
+            worker_key,
+            resource_account.resource_id
+        );
+        Ok(())
+    }
+
+    // Publish a new X25519 key for sealing job inputs to this resource. The previous
+    // key stays published so inputs sealed before the rotation can still be opened.
+    pub fn rotate_encryption_key(
+        ctx: Context<UpdateResource>,
+        encryption_key: [u8; 32],
+    ) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        require!(
+            encryption_key != [0; 32] && encryption_key != resource_account.encryption_key,
+            ErrorCode::InvalidEncryptionKey
+        );
+        resource_account.previous_encryption_key = resource_account.encryption_key;
+        resource_account.encryption_key = encryption_key;
+        resource_account.encryption_key_epoch += 1;
+
+        emit!(EncryptionKeyRotated {
+            resource_id: resource_account.resource_id,
+            epoch: resource_account.encryption_key_epoch,
+            encryption_key,
+        });
+
+        msg!(
+            "Resource {} encryption key rotated to epoch {}",
+            resource_account.resource_id,
+            resource_account.encryption_key_epoch
+        );
+        Ok(())
+    }
+
+    // Put a resource into maintenance mode. New matches are blocked while
+    // computations that are already matched can still complete.
+    pub fn deactivate_resource(ctx: Context<UpdateResource>) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        require!(resource_account.is_active, ErrorCode::ResourceNotActive);
+        resource_account.is_active = false;
+
+        msg!("Resource {} deactivated", resource_account.resource_id);
+        Ok(())
+    }
+
+    // Bring a resource back from maintenance mode
+    pub fn reactivate_resource(ctx: Context<UpdateResource>) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        require!(!resource_account.is_active, ErrorCode::ResourceAlreadyActive);
+        resource_account.is_active = true;
+
+        msg!("Resource {} reactivated", resource_account.resource_id);
+        Ok(())
+    }
+
     // Submit a computation request
     pub fn submit_computation_request(
         ctx: Context<SubmitComputationRequest>,
-        computation_type: String,
-        required_power: u64,
-        required_memory: u64,
+        job_category: u8,
+        required_accelerator: Option<u16>,
+        required_cpu_architecture: Option<u8>,
+        requirements: [u64; MAX_DIMENSIONS],
+        required_capabilities: u128,
+        optional_capabilities: u128,
         max_price_per_unit: u64,
-        preferred_location: Option<String>,
+        preferred_region: Option<[u8; 2]>,
+        preferred_zone: u8,
+        residency: Option<[u8; 4]>,
         duration_estimate: u64,
+        verification_key_hash: Option<[u8; 32]>,
+        required_tee: Option<TeeRequirement>,
+    ) -> Result<()> {
+        let args = RequestArgs {
+            job_category,
+            required_accelerator,
+            required_cpu_architecture,
+            requirements,
+            required_capabilities,
+            optional_capabilities,
+            max_price_per_unit,
+            preferred_region,
+            preferred_zone,
+            residency,
+            duration_estimate,
+            verification_key_hash,
+            required_tee,
+        };
+        init_request(
+            &mut ctx.accounts.system_state,
+            &mut ctx.accounts.request_account,
+            ctx.accounts.requester.key(),
+            args,
+        )?;
+
+        msg!(
+            "Computation request submitted with ID: {}",
+            ctx.accounts.request_account.request_id
+        );
+        Ok(())
+    }
+
+    // Submit a request together with a provider's signed offer and match them in one
+    // step. The instruction before this one must verify the provider's Ed25519
+    // signature over the offer.
+    pub fn submit_with_offer(
+        ctx: Context<SubmitWithOffer>,
+        request: RequestArgs,
+        offer: OfferArgs,
     ) -> Result<()> {
+        init_request(
+            &mut ctx.accounts.system_state,
+            &mut ctx.accounts.request_account,
+            ctx.accounts.requester.key(),
+            request,
+        )?;
+
         let system_state = &mut ctx.accounts.system_state;
         let request_account = &mut ctx.accounts.request_account;
-        let requester = &ctx.accounts.requester;
-
-        // Create a new request entry
-        request_account.request_id = system_state.computation_request_count;
-        request_account.requester = requester.key();
-        request_account.computation_type = computation_type;
-        request_account.required_power = required_power;
-        request_account.required_memory = required_memory;
-        request_account.max_price_per_unit = max_price_per_unit;
-        request_account.preferred_location = preferred_location;
-        request_account.duration_estimate = duration_estimate;
-        request_account.status = RequestStatus::Pending;
-        request_account.matched_resource = None;
+        let resource_account = &mut ctx.accounts.resource_account;
 
-        // Update system state
-        system_state.computation_request_count += 1;
+        // Only a live, unrevoked offer signed by the resource's provider is accepted
+        let offer = ProviderOffer {
+            resource: resource_account.key(),
+            price_per_unit: offer.price_per_unit,
+            capacity: parse_resource_vector(offer.capacity)?,
+            expiry: offer.expiry,
+            nonce: offer.nonce,
+        };
+        require!(
+            Clock::get()?.unix_timestamp < offer.expiry,
+            ErrorCode::OfferExpired
+        );
+        require!(
+            offer.nonce >= resource_account.min_offer_nonce,
+            ErrorCode::OfferRevoked
+        );
+        let signer = preceding_ed25519_signer(&ctx.accounts.instructions, &offer.message())
+            .map_err(|e| {
+                msg!("Offer signature rejected: {}", e);
+                error!(ErrorCode::InvalidOfferSignature)
+            })?;
+        require!(
+            signer == resource_account.provider,
+            ErrorCode::InvalidOfferSignature
+        );
+        require!(
+            offer.price_per_unit <= request_account.max_price_per_unit,
+            ErrorCode::PriceTooHigh
+        );
+
+        let requirements = check_eligibility(
+            request_account,
+            resource_account,
+            ctx.accounts.benchmark.as_ref(),
+            ctx.accounts.tee_attestation.as_ref(),
+        )?;
+        require!(
+            offer.capacity.covers(&requirements).is_ok(),
+            ErrorCode::OfferCapacityExceeded
+        );
+
+        assign_match(system_state, request_account, resource_account, &requirements)?;
+        request_account.agreed_price_per_unit = Some(offer.price_per_unit);
+
+        // Record the nonce so the same offer cannot be settled twice
+        let receipt = &mut ctx.accounts.offer_receipt;
+        receipt.resource = resource_account.key();
+        receipt.nonce = offer.nonce;
+        receipt.request = request_account.key();
+
+        msg!(
+            "Computation request {} matched with resource {} at {} via signed offer {}",
+            request_account.request_id,
+            resource_account.resource_id,
+            offer.price_per_unit,
+            offer.nonce
+        );
+        Ok(())
+    }
+
+    // Invalidate every signed offer for a resource with a nonce below the given one
+    pub fn revoke_offers(ctx: Context<UpdateResource>, min_offer_nonce: u64) -> Result<()> {
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        require!(
+            min_offer_nonce > resource_account.min_offer_nonce,
+            ErrorCode::InvalidOfferNonce
+        );
+        resource_account.min_offer_nonce = min_offer_nonce;
 
-        msg!("Computation request submitted with ID: {}", request_account.request_id);
+        msg!(
+            "Offers below nonce {} revoked for resource {}",
+            min_offer_nonce,
+            resource_account.resource_id
+        );
         Ok(())
     }
 
@@ -96,55 +394,609 @@ pub mod resource_matching {
         let request_account = &mut ctx.accounts.request_account;
         let resource_account = &mut ctx.accounts.resource_account;
 
-        // Verify the matcher has appropriate permission
+        match_pair(
+            system_state,
+            ctx.accounts.matcher.key(),
+            request_account,
+            resource_account,
+            ctx.accounts.benchmark.as_ref(),
+            ctx.accounts.tee_attestation.as_ref(),
+        )?;
+
+        // The soft location preference only informs ranking, it never blocks a match
+        let locality = request_account.preferred_region.map(|preferred| {
+            Locality::between(
+                RegionCode(preferred),
+                request_account.preferred_zone,
+                RegionCode(resource_account.region),
+                resource_account.zone,
+            )
+        });
+
+        let optional_matched = CapabilitySet(resource_account.capabilities)
+            .matched_count(CapabilitySet(request_account.optional_capabilities));
+
+        msg!(
+            "Resource {} matched with request {} (locality: {:?}, optional capabilities: {})",
+            resource_id,
+            request_id,
+            locality,
+            optional_matched
+        );
+        Ok(())
+    }
+
+    // Apply many (request, resource) matches in one transaction. Each pair is passed as
+    // remaining accounts in the order request, resource, then the pair's benchmark and
+    // TEE attestations when flagged. Every pair is validated and failures are logged
+    // with their error, but matches are only applied if all pairs pass.
+    pub fn batch_match<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMatch<'info>>,
+        pairs: Vec<BatchPair>,
+    ) -> Result<()> {
+        require!(
+            !pairs.is_empty() && pairs.len() <= MAX_BATCH_PAIRS,
+            ErrorCode::InvalidBatchSize
+        );
+
+        let system_state = &mut ctx.accounts.system_state;
+        let matcher = ctx.accounts.matcher.key();
+        let accounts = &mut ctx.remaining_accounts.iter();
+
+        // A resource can take several requests in one batch, so each resource is loaded
+        // once and later pairs see the capacity reserved by earlier ones
+        let mut requests: Vec<Account<'info, ComputationRequest>> = Vec::with_capacity(pairs.len());
+        let mut resources: BTreeMap<Pubkey, Account<'info, ResourceAccount>> = BTreeMap::new();
+        let mut failed = 0;
+
+        for (index, pair) in pairs.iter().enumerate() {
+            let request_info = next_writable_account(accounts)?;
+            let resource_info = next_writable_account(accounts)?;
+            let benchmark = if pair.has_benchmark {
+                let info = next_account_info(accounts)?;
+                Some(Account::<BenchmarkAttestation>::try_from(info)?)
+            } else {
+                None
+            };
+            let tee_attestation = if pair.has_tee_attestation {
+                let info = next_account_info(accounts)?;
+                Some(Account::<TeeAttestation>::try_from(info)?)
+            } else {
+                None
+            };
+
+            require!(
+                requests.iter().all(|request| request.key() != request_info.key()),
+                ErrorCode::DuplicateBatchRequest
+            );
+            let mut request_account = Account::<ComputationRequest>::try_from(request_info)?;
+            if !resources.contains_key(resource_info.key) {
+                resources.insert(
+                    resource_info.key(),
+                    Account::<ResourceAccount>::try_from(resource_info)?,
+                );
+            }
+            let resource_account = resources.get_mut(resource_info.key).unwrap();
+
+            if let Err(e) = match_pair(
+                system_state,
+                matcher,
+                &mut request_account,
+                resource_account,
+                benchmark.as_ref(),
+                tee_attestation.as_ref(),
+            ) {
+                msg!(
+                    "Pair {} (request {}, resource {}) rejected: {}",
+                    index,
+                    request_account.request_id,
+                    resource_account.resource_id,
+                    e
+                );
+                failed += 1;
+            }
+            requests.push(request_account);
+        }
+
+        require!(accounts.next().is_none(), ErrorCode::InvalidBatchSize);
+        if failed > 0 {
+            msg!("{} of {} pairs rejected, no matches applied", failed, pairs.len());
+            return err!(ErrorCode::BatchMatchFailed);
+        }
+
+        // Remaining accounts are not persisted automatically
+        for request_account in &requests {
+            request_account.exit(ctx.program_id)?;
+        }
+        for resource_account in resources.values() {
+            resource_account.exit(ctx.program_id)?;
+        }
+
+        msg!("Batch of {} matches applied", pairs.len());
+        Ok(())
+    }
+
+    // Take on a match offered to one of the provider's resources
+    pub fn accept_match(ctx: Context<AcceptMatch>) -> Result<()> {
+        let request_account = &mut ctx.accounts.request_account;
+        let resource_account = &mut ctx.accounts.resource_account;
+
         require!(
-            ctx.accounts.matcher.key() == system_state.authority 
-            || ctx.accounts.matcher.key() == resource_account.provider,
+            request_account.status == RequestStatus::Offered,
+            ErrorCode::RequestNotOffered
+        );
+        require!(
+            request_account.matched_resource == Some(resource_account.key()),
+            ErrorCode::ResourceMismatch
+        );
+        require!(
+            Clock::get()?.unix_timestamp < request_account.offer_expires_at,
+            ErrorCode::MatchOfferExpired
+        );
+
+        request_account.status = RequestStatus::Matched;
+        request_account.offer_expires_at = 0;
+        resource_account.offers_accepted += 1;
+
+        msg!(
+            "Resource {} accepted request {}",
+            resource_account.resource_id,
+            request_account.request_id
+        );
+        Ok(())
+    }
+
+    // Turn down an offered match. The request goes back to pending for other resources.
+    pub fn reject_match(ctx: Context<RejectMatch>) -> Result<()> {
+        let request_account = &mut ctx.accounts.request_account;
+        require!(
+            request_account.status == RequestStatus::Offered,
+            ErrorCode::RequestNotOffered
+        );
+
+        decline_offer(
+            &mut ctx.accounts.system_state,
+            request_account,
+            &mut ctx.accounts.resource_account,
+            false,
+        )
+    }
+
+    // Return a match offer the provider let time out to pending. Anyone can call this.
+    pub fn expire_match_offer(ctx: Context<ExpireMatchOffer>) -> Result<()> {
+        let request_account = &mut ctx.accounts.request_account;
+        require!(
+            request_account.status == RequestStatus::Offered,
+            ErrorCode::RequestNotOffered
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= request_account.offer_expires_at,
+            ErrorCode::MatchOfferNotExpired
+        );
+
+        decline_offer(
+            &mut ctx.accounts.system_state,
+            request_account,
+            &mut ctx.accounts.resource_account,
+            true,
+        )
+    }
+
+    // Rank the resources a pending request would rather run on. Entries are resource
+    // accounts or provider wallets, most preferred first. Unlisted resources rank last.
+    pub fn set_request_preferences(
+        ctx: Context<SetRequestPreferences>,
+        ranked: Vec<Pubkey>,
+    ) -> Result<()> {
+        let request_account = &ctx.accounts.request_account;
+        require!(
+            request_account.status == RequestStatus::Pending,
+            ErrorCode::RequestNotPending
+        );
+        validate_preferences(&ranked)?;
+
+        let preferences = &mut ctx.accounts.preferences;
+        preferences.subject = request_account.key();
+        preferences.owner = ctx.accounts.requester.key();
+        preferences.ranked = ranked;
+
+        msg!(
+            "Request {} ranks {} preferred resources",
+            request_account.request_id,
+            preferences.ranked.len()
+        );
+        Ok(())
+    }
+
+    // Rank the requests a resource would rather serve. Entries are request accounts or
+    // requester wallets, most preferred first. Unlisted requests rank last.
+    pub fn set_resource_preferences(
+        ctx: Context<SetResourcePreferences>,
+        ranked: Vec<Pubkey>,
+    ) -> Result<()> {
+        validate_preferences(&ranked)?;
+
+        let resource_account = &ctx.accounts.resource_account;
+        let preferences = &mut ctx.accounts.preferences;
+        preferences.subject = resource_account.key();
+        preferences.owner = ctx.accounts.provider.key();
+        preferences.ranked = ranked;
+
+        msg!(
+            "Resource {} ranks {} preferred requesters",
+            resource_account.resource_id,
+            preferences.ranked.len()
+        );
+        Ok(())
+    }
+
+    // Remove a preference list and return its rent
+    pub fn close_preferences(ctx: Context<ClosePreferences>) -> Result<()> {
+        msg!("Preferences for {} closed", ctx.accounts.preferences.subject);
+        Ok(())
+    }
+
+    // Apply a one-to-one matching that honors the preferences of both sides. Remaining
+    // accounts list every request and then every resource taking part, each followed by
+    // its preferences PDA. The PDA must be passed even if it was never created, so the
+    // matcher cannot leave a participant's preferences out.
+    //
+    // The matching is rejected if some request and resource among the participants are
+    // eligible for each other and would both rather be matched together than keep what
+    // the matching gives them. Eligibility is judged without benchmark or TEE
+    // attestations, so requests that need them are matched through batch_match instead.
+    pub fn stable_match<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMatch<'info>>,
+        request_count: u8,
+        pairs: Vec<StablePair>,
+    ) -> Result<()> {
+        let system_state = &mut ctx.accounts.system_state;
+        require!(
+            ctx.accounts.matcher.key() == system_state.authority,
             ErrorCode::UnauthorizedMatcher
         );
 
-        // Check if request is pending
+        let accounts = ctx.remaining_accounts;
+        let request_count = request_count as usize;
+        require!(
+            accounts.len() % 2 == 0
+                && accounts.len() / 2 <= MAX_STABLE_PARTICIPANTS
+                && request_count <= accounts.len() / 2,
+            ErrorCode::InvalidBatchSize
+        );
+        let (request_entries, resource_entries) = accounts.split_at(request_count * 2);
+
+        let mut requests: Vec<Account<'info, ComputationRequest>> = Vec::new();
+        let mut request_preferences = Vec::new();
+        for entry in request_entries.chunks(2) {
+            require!(entry[0].is_writable, ErrorCode::AccountNotWritable);
+            require!(
+                requests.iter().all(|request| request.key() != entry[0].key()),
+                ErrorCode::DuplicateBatchRequest
+            );
+            requests.push(Account::try_from(&entry[0])?);
+            request_preferences.push(load_preferences(ctx.program_id, entry[0].key, &entry[1])?);
+        }
+
+        let mut resources: Vec<Account<'info, ResourceAccount>> = Vec::new();
+        let mut resource_preferences = Vec::new();
+        for entry in resource_entries.chunks(2) {
+            require!(entry[0].is_writable, ErrorCode::AccountNotWritable);
+            require!(
+                resources.iter().all(|resource| resource.key() != entry[0].key()),
+                ErrorCode::InvalidStableMatching
+            );
+            resources.push(Account::try_from(&entry[0])?);
+            resource_preferences.push(load_preferences(ctx.program_id, entry[0].key, &entry[1])?);
+        }
+
+        // Every participant is matched at most once
+        let mut request_match: Vec<Option<usize>> = vec![None; requests.len()];
+        let mut resource_match: Vec<Option<usize>> = vec![None; resources.len()];
+        for pair in &pairs {
+            let (request, resource) = (pair.request as usize, pair.resource as usize);
+            require!(
+                request < requests.len()
+                    && resource < resources.len()
+                    && request_match[request].is_none()
+                    && resource_match[resource].is_none(),
+                ErrorCode::InvalidStableMatching
+            );
+            request_match[request] = Some(resource);
+            resource_match[resource] = Some(request);
+        }
+
+        // Pairs that could be matched, judged on the state before this matching
+        let eligible: Vec<Vec<bool>> = requests
+            .iter()
+            .map(|request_account| {
+                resources
+                    .iter()
+                    .map(|resource_account| {
+                        request_account.status == RequestStatus::Pending
+                            && request_account.declined_resource != Some(resource_account.key())
+                            && resource_account.price_per_unit <= request_account.max_price_per_unit
+                            && check_eligibility(request_account, resource_account, None, None)
+                                .is_ok()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let resource_rank = |request: usize, resource: usize| {
+            preference_rank(
+                &request_preferences[request],
+                resources[resource].key(),
+                resources[resource].provider,
+            )
+        };
+        let request_rank = |resource: usize, request: usize| {
+            preference_rank(
+                &resource_preferences[resource],
+                requests[request].key(),
+                requests[request].requester,
+            )
+        };
+
+        for (request, row) in eligible.iter().enumerate() {
+            if let Some(resource) = request_match[request] {
+                require!(row[resource], ErrorCode::InvalidStableMatching);
+            }
+            for (resource, &is_eligible) in row.iter().enumerate() {
+                if !is_eligible || request_match[request] == Some(resource) {
+                    continue;
+                }
+                let request_prefers = request_match[request].map_or(true, |current| {
+                    resource_rank(request, resource) < resource_rank(request, current)
+                });
+                let resource_prefers = resource_match[resource].map_or(true, |current| {
+                    request_rank(resource, request) < request_rank(resource, current)
+                });
+                if request_prefers && resource_prefers {
+                    msg!(
+                        "Request {} and resource {} form a blocking pair",
+                        requests[request].request_id,
+                        resources[resource].resource_id
+                    );
+                    return err!(ErrorCode::BlockingPair);
+                }
+            }
+        }
+
+        let matcher = ctx.accounts.matcher.key();
+        for pair in &pairs {
+            let request_account = &mut requests[pair.request as usize];
+            let resource_account = &mut resources[pair.resource as usize];
+            match_pair(system_state, matcher, request_account, resource_account, None, None)?;
+            request_account.exit(ctx.program_id)?;
+            resource_account.exit(ctx.program_id)?;
+        }
+
+        msg!(
+            "Stable matching of {} pairs applied over {} requests and {} resources",
+            pairs.len(),
+            requests.len(),
+            resources.len()
+        );
+        Ok(())
+    }
+
+    // Put a pending request up for a sealed-bid auction instead of direct matching
+    pub fn open_auction(
+        ctx: Context<OpenAuction>,
+        bidding_period: i64,
+        reveal_period: i64,
+        deposit: u64,
+    ) -> Result<()> {
+        let request_account = &mut ctx.accounts.request_account;
+
         require!(
             request_account.status == RequestStatus::Pending,
             ErrorCode::RequestNotPending
         );
+        require!(
+            bidding_period > 0 && reveal_period > 0,
+            ErrorCode::InvalidAuctionPeriod
+        );
 
-        // Check if resource is active
-        require!(resource_account.is_active, ErrorCode::ResourceNotActive);
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.auction;
+        auction.request = request_account.key();
+        auction.requester = request_account.requester;
+        auction.bid_deadline = now.saturating_add(bidding_period);
+        auction.reveal_deadline = auction.bid_deadline.saturating_add(reveal_period);
+        auction.deposit = deposit;
+        auction.bid_count = 0;
+        auction.open_bids = 0;
+        auction.best_price = None;
+        auction.best_resource = None;
+        request_account.status = RequestStatus::Auctioning;
+
+        msg!(
+            "Auction opened for request {}: bids until {}, reveals until {}",
+            request_account.request_id,
+            auction.bid_deadline,
+            auction.reveal_deadline
+        );
+        Ok(())
+    }
+
+    // Commit to a hidden price for a resource and lock the auction deposit
+    pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32]) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
 
-        // Check if resource meets requirements
         require!(
-            resource_account.computation_power >= request_account.required_power,
-            ErrorCode::InsufficientComputationPower
+            Clock::get()?.unix_timestamp < auction.bid_deadline,
+            ErrorCode::BiddingClosed
         );
-        
+
+        let bid = &mut ctx.accounts.bid;
+        bid.auction = auction.key();
+        bid.resource = ctx.accounts.resource_account.key();
+        bid.provider = ctx.accounts.provider.key();
+        bid.commitment = commitment;
+
+        // The deposit sits in the bid account on top of its rent
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.provider.to_account_info(),
+                    to: ctx.accounts.bid.to_account_info(),
+                },
+            ),
+            auction.deposit,
+        )?;
+        auction.bid_count += 1;
+        auction.open_bids += 1;
+
+        msg!(
+            "Sealed bid committed for resource {}",
+            ctx.accounts.resource_account.resource_id
+        );
+        Ok(())
+    }
+
+    // Reveal a committed bid. The deposit is refunded whether or not the bid is valid;
+    // only valid bids that meet the requirements compete for the match.
+    pub fn reveal_bid(ctx: Context<RevealBid>, price_per_unit: u64, salt: [u8; 32]) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let request_account = &ctx.accounts.request_account;
+        let resource_account = &ctx.accounts.resource_account;
+
+        let now = Clock::get()?.unix_timestamp;
         require!(
-            resource_account.available_memory >= request_account.required_memory,
-            ErrorCode::InsufficientMemory
+            now >= auction.bid_deadline && now < auction.reveal_deadline,
+            ErrorCode::RevealClosed
         );
-        
         require!(
-            resource_account.price_per_unit <= request_account.max_price_per_unit,
-            ErrorCode::PriceTooHigh
+            bid_commitment(&auction.key(), &resource_account.key(), price_per_unit, &salt)
+                == ctx.accounts.bid.commitment,
+            ErrorCode::InvalidBidReveal
         );
+        auction.open_bids -= 1;
 
-        // Match the resource with the request
-        request_account.status = RequestStatus::Matched;
-        request_account.matched_resource = Some(resource_id);
-        
-        // Update system state
-        system_state.active_matches += 1;
+        let eligible = price_per_unit <= request_account.max_price_per_unit
+            && check_eligibility(
+                request_account,
+                resource_account,
+                ctx.accounts.benchmark.as_ref(),
+                ctx.accounts.tee_attestation.as_ref(),
+            )
+            .is_ok();
+        let lowest = auction.best_price.map_or(true, |best| price_per_unit < best);
+        if eligible && lowest {
+            auction.best_price = Some(price_per_unit);
+            auction.best_resource = Some(resource_account.key());
+        }
+
+        msg!(
+            "Bid of {} revealed for resource {} (eligible: {}, leading: {})",
+            price_per_unit,
+            resource_account.resource_id,
+            eligible,
+            eligible && lowest
+        );
+        Ok(())
+    }
+
+    // Forfeit the deposit of a bid that was not revealed in time to the requester
+    pub fn forfeit_bid(ctx: Context<ForfeitBid>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            Clock::get()?.unix_timestamp >= auction.reveal_deadline,
+            ErrorCode::AuctionNotEnded
+        );
+        auction.open_bids -= 1;
+
+        msg!("Unrevealed bid from {} forfeited", ctx.accounts.bid.provider);
+        Ok(())
+    }
+
+    // Match the request with the lowest valid bid once every bid is revealed or forfeited.
+    // Without a usable winner the request returns to pending.
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        let system_state = &mut ctx.accounts.system_state;
+        let request_account = &mut ctx.accounts.request_account;
+        let auction = &ctx.accounts.auction;
 
-        msg!("Resource {} matched with request {}", resource_id, request_id);
+        require!(
+            Clock::get()?.unix_timestamp >= auction.reveal_deadline,
+            ErrorCode::AuctionNotEnded
+        );
+        require!(auction.open_bids == 0, ErrorCode::UnresolvedBids);
+        require!(
+            request_account.status == RequestStatus::Auctioning,
+            ErrorCode::RequestNotAuctioning
+        );
+
+        request_account.status = RequestStatus::Pending;
+        let Some(winner) = auction.best_resource else {
+            msg!("Auction for request {} closed without a valid bid", request_account.request_id);
+            return Ok(());
+        };
+
+        let resource_account = ctx
+            .accounts
+            .resource_account
+            .as_mut()
+            .ok_or(ErrorCode::ResourceMismatch)?;
+        require!(resource_account.key() == winner, ErrorCode::ResourceMismatch);
+
+        // Anyone can settle, so leaving out the winner's attestations must fail rather
+        // than void the auction. Each resource has one attestation of each kind, so with
+        // these checks an ineligible winner has really lost eligibility.
+        if request_account.required_tee.is_some() {
+            let attestation = ctx
+                .accounts
+                .tee_attestation
+                .as_ref()
+                .ok_or(ErrorCode::TeeRequired)?;
+            require!(attestation.resource == winner, ErrorCode::TeeAttestationMismatch);
+        }
+        if ResourceVector(request_account.requirements).benchmarks() != ResourceVector::default() {
+            let benchmark = ctx
+                .accounts
+                .benchmark
+                .as_ref()
+                .ok_or(ErrorCode::BenchmarkRequired)?;
+            require!(benchmark.resource == winner, ErrorCode::BenchmarkMismatch);
+        }
+
+        // Capacity may have been taken or an attestation expired between the reveal and
+        // settlement
+        match check_eligibility(
+            request_account,
+            resource_account,
+            ctx.accounts.benchmark.as_ref(),
+            ctx.accounts.tee_attestation.as_ref(),
+        ) {
+            Ok(requirements) => {
+                assign_match(system_state, request_account, resource_account, &requirements)?;
+                request_account.agreed_price_per_unit = auction.best_price;
+                msg!(
+                    "Auction for request {} won by resource {} at {}",
+                    request_account.request_id,
+                    resource_account.resource_id,
+                    auction.best_price.unwrap_or_default()
+                );
+            }
+            Err(e) => {
+                msg!("Winning resource is no longer eligible: {}", e);
+            }
+        }
         Ok(())
     }
 
-    // Complete a computation task
+    // Complete a computation task. A provider reporting completion must be preceded by
+    // an Ed25519 verification of a worker attestation over the result hash and runtime.
     pub fn complete_computation(
         ctx: Context<CompleteComputation>,
         request_id: u64,
         actual_duration: u64,
         success: bool,
+        result_hash: Option<[u8; 32]>,
     ) -> Result<()> {
         let system_state = &mut ctx.accounts.system_state;
         let request_account = &mut ctx.accounts.request_account;
@@ -166,143 +1018,1857 @@ pub mod resource_matching {
 
         // Check if the matched resource is correct
         require!(
-            request_account.matched_resource == Some(resource_account.resource_id),
+            request_account.matched_resource == Some(resource_account.key()),
             ErrorCode::ResourceMismatch
         );
 
-        // Update resource stats
-        resource_account.total_usage_time += actual_duration;
-        
-        // If successful, update reputation score (simple implementation)
+        // The provider's wallet alone cannot vouch for the output, an enrolled worker must
+        if ctx.accounts.authority.key() == resource_account.provider {
+            let result_hash = result_hash.ok_or(ErrorCode::MissingResultAttestation)?;
+            let attestation = ResultAttestation {
+                request: request_account.key(),
+                result_hash,
+                runtime: actual_duration,
+            };
+            let worker_key = attestation
+                .verify_preceding(&ctx.accounts.instructions)
+                .map_err(|e| {
+                    msg!("Result attestation rejected: {}", e);
+                    error!(ErrorCode::InvalidResultAttestation)
+                })?;
+            require!(
+                resource_account.worker_keys.contains(&worker_key),
+                ErrorCode::UnknownWorkerKey
+            );
+            msg!("Result attested by worker {}", worker_key);
+        }
+        request_account.result_hash = result_hash;
+
+        // Verifiable requests only succeed through a proof checked by complete_with_proof.
+        // Their requester cannot report a failure either, which would reclaim the escrow
+        // from a provider that may still deliver a proof.
+        if request_account.verification_key_hash.is_some() {
+            require!(!success, ErrorCode::ProofRequired);
+            require!(
+                ctx.accounts.authority.key() != request_account.requester,
+                ErrorCode::UnauthorizedCompletion
+            );
+        }
+
+        // Update resource stats and release the reservation
+        resource_account.total_usage_time += actual_duration;
+        release_allocation(resource_account, request_account)?;
+        
+        // If successful, update reputation score (simple implementation)
         if success {
             resource_account.reputation_score = resource_account.reputation_score.saturating_add(1);
         } else {
             resource_account.reputation_score = resource_account.reputation_score.saturating_sub(1);
         }
 
-        // Update request status
-        request_account.status = if success {
-            RequestStatus::Completed
-        } else {
-            RequestStatus::Failed
-        };
+        // Update request status
+        request_account.status = if success {
+            RequestStatus::Completed
+        } else {
+            RequestStatus::Failed
+        };
+
+        // Update system state
+        system_state.active_matches -= 1;
+
+        msg!("Computation for request {} completed with status: {}", 
+            request_id, 
+            if success { "success" } else { "failure" }
+        );
+        
+        Ok(())
+    }
+
+    // Publish a Groth16 verifying key so requests can reference it by hash
+    pub fn register_verification_key(
+        ctx: Context<RegisterVerificationKey>,
+        hash: [u8; 32],
+        alpha_g1: [u8; G1_LEN],
+        beta_g2: [u8; G2_LEN],
+        gamma_g2: [u8; G2_LEN],
+        delta_g2: [u8; G2_LEN],
+        ic: Vec<[u8; G1_LEN]>,
+    ) -> Result<()> {
+        // Computation proofs are checked against the inputs complete_with_proof derives
+        require!(
+            ic.len() == COMPUTATION_PUBLIC_INPUTS + 1,
+            ErrorCode::InvalidVerificationKey
+        );
+        let key = Groth16VerifyingKey { alpha_g1, beta_g2, gamma_g2, delta_g2, ic };
+        require!(key.hash() == hash, ErrorCode::VerificationKeyHashMismatch);
+
+        let verification_key = &mut ctx.accounts.verification_key;
+        verification_key.hash = hash;
+        verification_key.alpha_g1 = key.alpha_g1;
+        verification_key.beta_g2 = key.beta_g2;
+        verification_key.gamma_g2 = key.gamma_g2;
+        verification_key.delta_g2 = key.delta_g2;
+        verification_key.ic = key.ic;
+
+        msg!(
+            "Verification key registered with {} public inputs",
+            verification_key.ic.len() - 1
+        );
+        Ok(())
+    }
+
+    // Deposit payment for a request into its escrow vault
+    pub fn fund_request(ctx: Context<FundRequest>, amount: u64) -> Result<()> {
+        let request_account = &mut ctx.accounts.request_account;
+
+        require!(
+            matches!(
+                request_account.status,
+                RequestStatus::Pending | RequestStatus::Offered | RequestStatus::Matched
+            ),
+            ErrorCode::RequestNotFundable
+        );
+        // Escrow is only released by complete_with_proof or refunded after a failure, so
+        // a request without a verification key would lock its payment on success
+        require!(
+            request_account.verification_key_hash.is_some(),
+            ErrorCode::EscrowRequiresProof
+        );
+
+        // transfer_checked is required by Token-2022 mints with a transfer fee
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: ctx.accounts.requester_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.requester.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        // A transfer fee is withheld from what arrives, so record what the vault now holds
+        ctx.accounts.escrow.reload()?;
+        request_account.escrow_amount = ctx.accounts.escrow.amount;
+
+        msg!(
+            "Request {} escrow funded with {}",
+            request_account.request_id,
+            request_account.escrow_amount
+        );
+        Ok(())
+    }
+
+    // Complete a verifiable request with a Groth16 proof and release its escrowed payment.
+    // The proof's public inputs commit to the request account and the result hash.
+    pub fn complete_with_proof(
+        ctx: Context<CompleteWithProof>,
+        actual_duration: u64,
+        result_hash: [u8; 32],
+        proof_a: [u8; G1_LEN],
+        proof_b: [u8; G2_LEN],
+        proof_c: [u8; G1_LEN],
+    ) -> Result<()> {
+        let system_state = &mut ctx.accounts.system_state;
+        let request_account = &mut ctx.accounts.request_account;
+        let resource_account = &mut ctx.accounts.resource_account;
+
+        require!(
+            request_account.status == RequestStatus::Matched,
+            ErrorCode::RequestNotMatched
+        );
+        require!(
+            request_account.matched_resource == Some(resource_account.key()),
+            ErrorCode::ResourceMismatch
+        );
+
+        let proof = Groth16Proof { a: proof_a, b: proof_b, c: proof_c };
+        let public_inputs = computation_public_inputs(&request_account.key(), &result_hash);
+        ctx.accounts
+            .verification_key
+            .to_groth16()
+            .verify(&proof, &public_inputs)
+            .map_err(|e| {
+                msg!("Proof rejected: {}", e);
+                error!(ErrorCode::ProofVerificationFailed)
+            })?;
+
+        // The proof stands in for the outcome, so the result is always a success
+        request_account.result_hash = Some(result_hash);
+        resource_account.total_usage_time += actual_duration;
+        resource_account.reputation_score = resource_account.reputation_score.saturating_add(1);
+        release_allocation(resource_account, request_account)?;
+        request_account.status = RequestStatus::Completed;
+        system_state.active_matches -= 1;
+
+        // Pay the provider out of escrow, signed by the escrow PDA
+        let amount = request_account.escrow_amount;
+        if amount > 0 {
+            let escrow = ctx.accounts.escrow.as_ref().ok_or(ErrorCode::EscrowRequired)?;
+            let provider_token_account = ctx
+                .accounts
+                .provider_token_account
+                .as_ref()
+                .ok_or(ErrorCode::EscrowRequired)?;
+            let mint = ctx.accounts.mint.as_ref().ok_or(ErrorCode::EscrowRequired)?;
+            require!(
+                provider_token_account.mint == escrow.mint && mint.key() == escrow.mint,
+                ErrorCode::EscrowRequired
+            );
+            let request_key = request_account.key();
+            let bump = ctx.bumps.escrow.ok_or(ErrorCode::EscrowRequired)?;
+            let seeds: &[&[u8]] = &[b"escrow", request_key.as_ref(), &[bump]];
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: escrow.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: provider_token_account.to_account_info(),
+                        authority: escrow.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                amount,
+                mint.decimals,
+            )?;
+            request_account.escrow_amount = 0;
+        }
+
+        msg!(
+            "Computation for request {} verified, {} released to the provider",
+            request_account.request_id,
+            amount
+        );
+        Ok(())
+    }
+
+    // Return the escrowed payment of a failed or cancelled request to the requester
+    pub fn refund_escrow(ctx: Context<RefundEscrow>) -> Result<()> {
+        let request_account = &mut ctx.accounts.request_account;
+
+        check_refundable(request_account)?;
+
+        let amount = request_account.escrow_amount;
+        let request_key = request_account.key();
+        let seeds: &[&[u8]] = &[b"escrow", request_key.as_ref(), &[ctx.bumps.escrow]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.requester_token_account.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        request_account.escrow_amount = 0;
+
+        msg!("Refunded {} for request {}", amount, request_account.request_id);
+        Ok(())
+    }
+
+    // Register an off-chain benchmark runner allowed to attest resource performance
+    pub fn add_verifier(ctx: Context<AddVerifier>) -> Result<()> {
+        let verifier = &mut ctx.accounts.verifier;
+        verifier.verifier = ctx.accounts.verifier_key.key();
+        verifier.is_active = true;
+
+        msg!("Benchmark verifier {} registered", verifier.verifier);
+        Ok(())
+    }
+
+    // Deregister a benchmark verifier
+    pub fn remove_verifier(ctx: Context<RemoveVerifier>) -> Result<()> {
+        msg!("Benchmark verifier {} removed", ctx.accounts.verifier.verifier);
+        Ok(())
+    }
+
+    // Record normalized benchmark scores for a resource, signed by a registered verifier
+    pub fn submit_benchmark(
+        ctx: Context<SubmitBenchmark>,
+        fp64_gflops: u64,
+        memory_bandwidth_gbps: u64,
+        tensor_score: u64,
+        valid_for: i64,
+    ) -> Result<()> {
+        require!(ctx.accounts.verifier.is_active, ErrorCode::UnauthorizedVerifier);
+        require!(valid_for > 0, ErrorCode::BenchmarkExpired);
+
+        let now = Clock::get()?.unix_timestamp;
+        let benchmark = &mut ctx.accounts.benchmark;
+        benchmark.resource = ctx.accounts.resource_account.key();
+        benchmark.verifier = ctx.accounts.verifier_key.key();
+        benchmark.fp64_gflops = fp64_gflops;
+        benchmark.memory_bandwidth_gbps = memory_bandwidth_gbps;
+        benchmark.tensor_score = tensor_score;
+        benchmark.attested_at = now;
+        benchmark.expires_at = now.saturating_add(valid_for);
+
+        msg!(
+            "Benchmark attested for resource {}: {} GFLOPS FP64, {} GB/s, tensor score {}",
+            ctx.accounts.resource_account.resource_id,
+            fp64_gflops,
+            memory_bandwidth_gbps,
+            tensor_score
+        );
+        Ok(())
+    }
+
+    // Register a remote attestation service allowed to vouch for provider enclaves
+    pub fn add_tee_verifier(ctx: Context<AddTeeVerifier>) -> Result<()> {
+        let verifier = &mut ctx.accounts.verifier;
+        verifier.verifier = ctx.accounts.verifier_key.key();
+        verifier.is_active = true;
+
+        msg!("TEE attestation verifier {} registered", verifier.verifier);
+        Ok(())
+    }
+
+    // Deregister a TEE attestation verifier
+    pub fn remove_tee_verifier(ctx: Context<RemoveTeeVerifier>) -> Result<()> {
+        msg!("TEE attestation verifier {} removed", ctx.accounts.verifier.verifier);
+        Ok(())
+    }
+
+    // Record the enclave a resource runs in, after the verifier has checked its quote
+    pub fn submit_tee_attestation(
+        ctx: Context<SubmitTeeAttestation>,
+        platform: u8,
+        measurement: [u8; TEE_MEASUREMENT_LEN],
+        valid_for: i64,
+    ) -> Result<()> {
+        require!(ctx.accounts.verifier.is_active, ErrorCode::UnauthorizedVerifier);
+        require!(valid_for > 0, ErrorCode::TeeAttestationExpired);
+        let platform = parse_tee_measurement(platform, &measurement)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let attestation = &mut ctx.accounts.tee_attestation;
+        attestation.resource = ctx.accounts.resource_account.key();
+        attestation.verifier = ctx.accounts.verifier_key.key();
+        attestation.platform = platform.code();
+        attestation.measurement = measurement;
+        attestation.attested_at = now;
+        attestation.expires_at = now.saturating_add(valid_for);
+
+        msg!(
+            "TEE attestation for resource {} on {:?} valid until {}",
+            ctx.accounts.resource_account.resource_id,
+            platform,
+            attestation.expires_at
+        );
+        Ok(())
+    }
+
+    // Cancel a request. A matched request releases its reservation on the resource.
+    pub fn cancel_request(ctx: Context<CancelRequest>) -> Result<()> {
+        let system_state = &mut ctx.accounts.system_state;
+        let request_account = &mut ctx.accounts.request_account;
+
+        check_cancellable(request_account)?;
+        match request_account.status {
+            RequestStatus::Pending => {}
+            _ => {
+                let resource_account = ctx
+                    .accounts
+                    .resource_account
+                    .as_mut()
+                    .ok_or(ErrorCode::ResourceMismatch)?;
+                require!(
+                    request_account.matched_resource == Some(resource_account.key()),
+                    ErrorCode::ResourceMismatch
+                );
+                release_allocation(resource_account, request_account)?;
+                system_state.active_matches -= 1;
+            }
+        }
+
+        request_account.status = RequestStatus::Cancelled;
+
+        msg!("Computation request {} cancelled", request_account.request_id);
+        Ok(())
+    }
+
+    // Grow a resource account to the current layout after new fields were added
+    pub fn migrate_resource(ctx: Context<MigrateResource>) -> Result<()> {
+        let resource_account = ctx.accounts.resource_account.to_account_info();
+        check_migratable(
+            &resource_account,
+            &ResourceAccount::DISCRIMINATOR[..],
+            &ctx.accounts.provider.key(),
+        )?;
+        grow_account(
+            &resource_account,
+            &ctx.accounts.provider,
+            &ctx.accounts.system_program,
+            8 + ResourceAccount::INIT_SPACE,
+        )?;
+        let resource =
+            ResourceAccount::try_deserialize(&mut &resource_account.try_borrow_data()?[..])
+                .map_err(|_| error!(ErrorCode::AccountNotMigratable))?;
+
+        msg!(
+            "Resource {} resized to {} bytes",
+            resource.resource_id,
+            8 + ResourceAccount::INIT_SPACE
+        );
+        Ok(())
+    }
+
+    // Grow a request account to the current layout after new fields were added
+    pub fn migrate_request(ctx: Context<MigrateRequest>) -> Result<()> {
+        let request_account = ctx.accounts.request_account.to_account_info();
+        check_migratable(
+            &request_account,
+            &ComputationRequest::DISCRIMINATOR[..],
+            &ctx.accounts.requester.key(),
+        )?;
+        grow_account(
+            &request_account,
+            &ctx.accounts.requester,
+            &ctx.accounts.system_program,
+            8 + ComputationRequest::INIT_SPACE,
+        )?;
+        let request =
+            ComputationRequest::try_deserialize(&mut &request_account.try_borrow_data()?[..])
+                .map_err(|_| error!(ErrorCode::AccountNotMigratable))?;
+
+        msg!(
+            "Computation request {} resized to {} bytes",
+            request.request_id,
+            8 + ComputationRequest::INIT_SPACE
+        );
+        Ok(())
+    }
+
+    // Close a request in a terminal status and return its rent to the requester
+    pub fn close_request(ctx: Context<CloseRequest>) -> Result<()> {
+        let request_account = &ctx.accounts.request_account;
+
+        require!(
+            matches!(
+                request_account.status,
+                RequestStatus::Completed | RequestStatus::Failed | RequestStatus::Cancelled
+            ),
+            ErrorCode::RequestNotTerminal
+        );
+        require!(request_account.escrow_amount == 0, ErrorCode::EscrowNotEmpty);
+
+        // Close the emptied escrow vault of a funded request too, returning its rent. A
+        // Token-2022 vault must have its withheld transfer fees harvested first.
+        let escrow = &ctx.accounts.escrow;
+        if !escrow.data_is_empty() {
+            require_keys_eq!(
+                *escrow.owner,
+                ctx.accounts.token_program.key(),
+                ErrorCode::EscrowProgramMismatch
+            );
+            let request_key = request_account.key();
+            let seeds: &[&[u8]] = &[b"escrow", request_key.as_ref(), &[ctx.bumps.escrow]];
+            token_interface::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::CloseAccount {
+                    account: escrow.to_account_info(),
+                    destination: ctx.accounts.requester.to_account_info(),
+                    authority: escrow.to_account_info(),
+                },
+                &[seeds],
+            ))?;
+        }
+
+        // Emit the final state so indexers keep the history after the account is gone
+        emit!(RequestClosed {
+            request_id: request_account.request_id,
+            requester: request_account.requester,
+            job_category: request_account.job_category,
+            requirements: request_account.requirements,
+            max_price_per_unit: request_account.max_price_per_unit,
+            duration_estimate: request_account.duration_estimate,
+            status: request_account.status.clone(),
+            matched_resource: request_account.matched_resource,
+        });
+
+        msg!("Computation request {} closed", request_account.request_id);
+        Ok(())
+    }
+
+    // Retire a resource with no active matches and return its rent to the provider
+    pub fn retire_resource(ctx: Context<RetireResource>) -> Result<()> {
+        let resource_account = &ctx.accounts.resource_account;
+
+        require!(
+            resource_account.active_matches == 0,
+            ErrorCode::ResourceHasActiveMatches
+        );
+
+        // Emit the final state so indexers keep the history after the account is gone
+        emit!(ResourceRetired {
+            resource_id: resource_account.resource_id,
+            provider: resource_account.provider,
+            accelerator: resource_account.accelerator,
+            cpu_architecture: resource_account.cpu_architecture,
+            capacity: resource_account.capacity,
+            region: resource_account.region,
+            price_per_unit: resource_account.price_per_unit,
+            reputation_score: resource_account.reputation_score,
+            total_usage_time: resource_account.total_usage_time,
+        });
+
+        msg!("Resource {} retired", resource_account.resource_id);
+        Ok(())
+    }
+}
+
+impl VerificationKey {
+    pub fn to_groth16(&self) -> Groth16VerifyingKey {
+        Groth16VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            ic: self.ic.clone(),
+        }
+    }
+}
+
+impl ResourceAccount {
+    // Share of match offers the provider accepted, in basis points. A provider that has
+    // not been offered a match yet has a full rate.
+    pub fn acceptance_rate_bps(&self) -> u64 {
+        let offers = self.offers_accepted + self.offers_declined;
+        if offers == 0 {
+            return 10_000;
+        }
+        self.offers_accepted * 10_000 / offers
+    }
+}
+
+impl BenchmarkAttestation {
+    // Attested scores as a resource vector over the benchmark dimensions
+    pub fn scores(&self) -> ResourceVector {
+        let mut scores = ResourceVector::default();
+        scores.set(Dimension::ComputationPower, self.fp64_gflops);
+        scores.set(Dimension::MemoryBandwidth, self.memory_bandwidth_gbps);
+        scores.set(Dimension::TensorScore, self.tensor_score);
+        scores
+    }
+}
+
+// Validate request parameters and fill in a newly created request account
+fn init_request(
+    system_state: &mut SystemState,
+    request_account: &mut Account<ComputationRequest>,
+    requester: Pubkey,
+    args: RequestArgs,
+) -> Result<()> {
+    let job_category = parse_job_category(args.job_category)?;
+    parse_resource_vector(args.requirements)?;
+    let required_accelerator = args
+        .required_accelerator
+        .map(parse_accelerator)
+        .transpose()?;
+    let required_cpu_architecture = args
+        .required_cpu_architecture
+        .map(parse_cpu_architecture)
+        .transpose()?;
+    let preferred_region = args.preferred_region.map(parse_region).transpose()?;
+    let residency = args.residency.map(parse_region_scope).transpose()?;
+    if let Some(required_tee) = &args.required_tee {
+        parse_tee_measurement(required_tee.platform, &required_tee.measurement)?;
+    }
+
+    // Create a new request entry
+    request_account.request_id = system_state.computation_request_count;
+    request_account.requester = requester;
+    request_account.job_category = job_category.code();
+    request_account.required_accelerator = required_accelerator.map(AcceleratorModel::code);
+    request_account.required_cpu_architecture =
+        required_cpu_architecture.map(CpuArchitecture::code);
+    request_account.requirements = args.requirements;
+    request_account.required_capabilities = args.required_capabilities;
+    request_account.optional_capabilities = args.optional_capabilities;
+    request_account.max_price_per_unit = args.max_price_per_unit;
+    request_account.preferred_region = preferred_region.map(RegionCode::code);
+    request_account.preferred_zone = args.preferred_zone;
+    request_account.residency = residency.map(RegionScope::code);
+    request_account.duration_estimate = args.duration_estimate;
+    request_account.status = RequestStatus::Pending;
+    request_account.matched_resource = None;
+    request_account.result_hash = None;
+    request_account.verification_key_hash = args.verification_key_hash;
+    request_account.escrow_amount = 0;
+    request_account.required_tee = args.required_tee;
+    request_account.agreed_price_per_unit = None;
+    request_account.offer_expires_at = 0;
+    request_account.declined_resource = None;
+
+    // Update system state
+    system_state.computation_request_count += 1;
+    Ok(())
+}
+
+// Commitment a provider posts for a sealed bid. Binding the auction and resource keeps
+// a commitment from being copied into another auction or onto another resource.
+pub fn bid_commitment(
+    auction: &Pubkey,
+    resource: &Pubkey,
+    price_per_unit: u64,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    keccak::hashv(&[
+        auction.as_ref(),
+        resource.as_ref(),
+        &price_per_unit.to_le_bytes(),
+        salt,
+    ])
+    .0
+}
+
+// Check that a resource can take on a request, returning the capacity a match reserves
+fn check_eligibility(
+    request_account: &Account<ComputationRequest>,
+    resource_account: &Account<ResourceAccount>,
+    benchmark: Option<&Account<BenchmarkAttestation>>,
+    tee_attestation: Option<&Account<TeeAttestation>>,
+) -> Result<ResourceVector> {
+    // Check if resource is active
+    require!(resource_account.is_active, ErrorCode::ResourceNotActive);
+
+    // Enforce the hard data residency constraint
+    if let Some(residency) = request_account.residency {
+        require!(
+            parse_region_scope(residency)?
+                .contains(RegionCode(resource_account.region), resource_account.zone),
+            ErrorCode::ResidencyViolation
+        );
+    }
+
+    // Check if resource meets requirements
+    if let Some(accelerator) = request_account.required_accelerator {
+        require!(
+            resource_account.accelerator == accelerator,
+            ErrorCode::AcceleratorMismatch
+        );
+    }
+
+    if let Some(cpu_architecture) = request_account.required_cpu_architecture {
+        require!(
+            resource_account.cpu_architecture == cpu_architecture,
+            ErrorCode::CpuArchitectureMismatch
+        );
+    }
+
+    // Every required software capability must be published by the provider
+    let missing = CapabilitySet(resource_account.capabilities)
+        .missing(CapabilitySet(request_account.required_capabilities));
+    if !missing.is_empty() {
+        msg!("Missing required capabilities: {:#034x}", missing.0);
+        return err!(ErrorCode::MissingCapability);
+    }
+
+    // A TEE requirement is only met by a current attestation of the exact enclave
+    if let Some(required_tee) = &request_account.required_tee {
+        let attestation = tee_attestation.ok_or(ErrorCode::TeeRequired)?;
+        require!(
+            attestation.resource == resource_account.key(),
+            ErrorCode::TeeAttestationMismatch
+        );
+        require!(
+            Clock::get()?.unix_timestamp < attestation.expires_at,
+            ErrorCode::TeeAttestationExpired
+        );
+        require!(
+            attestation.platform == required_tee.platform
+                && attestation.measurement == required_tee.measurement,
+            ErrorCode::TeeMeasurementMismatch
+        );
+    }
+
+    // Benchmark thresholds are checked against the verifier-attested scores
+    let requirements = ResourceVector(request_account.requirements);
+    let attested = match benchmark {
+        Some(benchmark) => {
+            require!(
+                benchmark.resource == resource_account.key(),
+                ErrorCode::BenchmarkMismatch
+            );
+            require!(
+                Clock::get()?.unix_timestamp < benchmark.expires_at,
+                ErrorCode::BenchmarkExpired
+            );
+            benchmark.scores()
+        }
+        None => ResourceVector::default(),
+    };
+    if let Err(dimension) = attested.covers(&requirements.benchmarks()) {
+        msg!("Attested benchmark below requirement in dimension {:?}", dimension);
+        return Err(match dimension {
+            Dimension::ComputationPower => ErrorCode::InsufficientComputationPower,
+            _ => ErrorCode::InsufficientBenchmarkScore,
+        }
+        .into());
+    }
+
+    // Compare the unreserved capacity against the requirements dimension by dimension
+    let requirements = requirements.consumable();
+    let remaining = ResourceVector(resource_account.capacity)
+        .checked_sub(&ResourceVector(resource_account.allocated))
+        .ok_or(ErrorCode::InsufficientCapacity)?;
+    if let Err(dimension) = remaining.covers(&requirements) {
+        msg!("Insufficient capacity in dimension {:?}", dimension);
+        return Err(match dimension {
+            Dimension::ComputationPower => ErrorCode::InsufficientComputationPower,
+            Dimension::MemoryGb => ErrorCode::InsufficientMemory,
+            _ => ErrorCode::InsufficientCapacity,
+        }
+        .into());
+    }
+    Ok(requirements)
+}
+
+// Authorize and apply a single match between a pending request and a resource
+fn match_pair(
+    system_state: &mut SystemState,
+    matcher: Pubkey,
+    request_account: &mut Account<ComputationRequest>,
+    resource_account: &mut Account<ResourceAccount>,
+    benchmark: Option<&Account<BenchmarkAttestation>>,
+    tee_attestation: Option<&Account<TeeAttestation>>,
+) -> Result<()> {
+    // Verify the matcher has appropriate permission
+    require!(
+        matcher == system_state.authority || matcher == resource_account.provider,
+        ErrorCode::UnauthorizedMatcher
+    );
+
+    // Check if request is pending
+    require!(
+        request_account.status == RequestStatus::Pending,
+        ErrorCode::RequestNotPending
+    );
+
+    // A resource that turned the request down is not offered it again
+    let offered = matcher != resource_account.provider;
+    require!(
+        !offered || request_account.declined_resource != Some(resource_account.key()),
+        ErrorCode::MatchDeclined
+    );
+
+    let requirements =
+        check_eligibility(request_account, resource_account, benchmark, tee_attestation)?;
+
+    require!(
+        resource_account.price_per_unit <= request_account.max_price_per_unit,
+        ErrorCode::PriceTooHigh
+    );
+
+    // Match the resource with the request and reserve its share of the capacity
+    assign_match(system_state, request_account, resource_account, &requirements)?;
+    request_account.agreed_price_per_unit = Some(resource_account.price_per_unit);
+
+    // A provider matching its own resource has agreed to the work. Any other match is
+    // only offered, and holds the capacity until the provider accepts or it times out.
+    if offered {
+        request_account.status = RequestStatus::Offered;
+        request_account.offer_expires_at =
+            Clock::get()?.unix_timestamp.saturating_add(MATCH_OFFER_TIMEOUT);
+    }
+    Ok(())
+}
+
+// Next remaining account, which the batch will write to
+fn next_writable_account<'a, 'info>(
+    accounts: &mut std::slice::Iter<'a, AccountInfo<'info>>,
+) -> Result<&'a AccountInfo<'info>> {
+    let account = next_account_info(accounts)?;
+    require!(account.is_writable, ErrorCode::AccountNotWritable);
+    Ok(account)
+}
+
+// Reject preference lists that are too long or name the same entry twice
+fn validate_preferences(ranked: &[Pubkey]) -> Result<()> {
+    require!(ranked.len() <= MAX_PREFERENCES, ErrorCode::InvalidPreferences);
+    for (index, entry) in ranked.iter().enumerate() {
+        require!(!ranked[..index].contains(entry), ErrorCode::InvalidPreferences);
+    }
+    Ok(())
+}
+
+// Ranked entries of the preferences PDA for a request or resource. A PDA that was
+// never created means no preferences.
+fn load_preferences<'info>(
+    program_id: &Pubkey,
+    subject: &Pubkey,
+    preferences: &'info AccountInfo<'info>,
+) -> Result<Vec<Pubkey>> {
+    let (address, _) =
+        Pubkey::find_program_address(&[b"preferences", subject.as_ref()], program_id);
+    require!(preferences.key() == address, ErrorCode::PreferencesMismatch);
+    if preferences.data_is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(Account::<Preferences>::try_from(preferences)?.into_inner().ranked)
+}
+
+// Position of a counterpart in a preference list, matched by its account or its
+// owner's wallet. Unlisted counterparts share the last rank.
+fn preference_rank(ranked: &[Pubkey], account: Pubkey, owner: Pubkey) -> usize {
+    ranked
+        .iter()
+        .position(|entry| *entry == account || *entry == owner)
+        .unwrap_or(ranked.len())
+}
+
+// Mark a request as matched and reserve its share of the resource's capacity
+fn assign_match(
+    system_state: &mut SystemState,
+    request_account: &mut ComputationRequest,
+    resource_account: &mut Account<ResourceAccount>,
+    requirements: &ResourceVector,
+) -> Result<()> {
+    request_account.status = RequestStatus::Matched;
+    request_account.matched_resource = Some(resource_account.key());
+    resource_account.allocated = ResourceVector(resource_account.allocated)
+        .checked_add(requirements)
+        .ok_or(ErrorCode::InsufficientCapacity)?
+        .0;
+    resource_account.active_matches += 1;
+    system_state.active_matches += 1;
+    Ok(())
+}
+
+// Release an offered match that was rejected or timed out and count it against the
+// provider. The request is not offered to the same resource again unless its provider
+// matches it directly.
+fn decline_offer(
+    system_state: &mut SystemState,
+    request_account: &mut ComputationRequest,
+    resource_account: &mut Account<ResourceAccount>,
+    expired: bool,
+) -> Result<()> {
+    require!(
+        request_account.matched_resource == Some(resource_account.key()),
+        ErrorCode::ResourceMismatch
+    );
+
+    release_allocation(resource_account, request_account)?;
+    system_state.active_matches -= 1;
+    resource_account.offers_declined += 1;
+
+    request_account.status = RequestStatus::Pending;
+    request_account.matched_resource = None;
+    request_account.agreed_price_per_unit = None;
+    request_account.offer_expires_at = 0;
+    request_account.declined_resource = Some(resource_account.key());
+
+    emit!(MatchOfferDeclined {
+        request_id: request_account.request_id,
+        resource_id: resource_account.resource_id,
+        expired,
+        acceptance_rate_bps: resource_account.acceptance_rate_bps(),
+    });
+
+    msg!(
+        "Resource {} declined request {}, acceptance rate {} bps",
+        resource_account.resource_id,
+        request_account.request_id,
+        resource_account.acceptance_rate_bps()
+    );
+    Ok(())
+}
+
+// Return a request's reserved capacity to its matched resource
+fn release_allocation(
+    resource_account: &mut ResourceAccount,
+    request_account: &ComputationRequest,
+) -> Result<()> {
+    resource_account.allocated = ResourceVector(resource_account.allocated)
+        .checked_sub(&ResourceVector(request_account.requirements).consumable())
+        .ok_or(ErrorCode::AllocationUnderflow)?
+        .0;
+    resource_account.active_matches -= 1;
+    Ok(())
+}
+
+// A requester can cancel a request until a provider holds it. Once matched, a request
+// paid through escrow belongs to the provider until it submits a proof or the system
+// authority fails the request, since cancelling would let the requester refund the
+// payment while the provider runs the job.
+fn check_cancellable(request_account: &ComputationRequest) -> Result<()> {
+    match request_account.status {
+        RequestStatus::Pending | RequestStatus::Offered => Ok(()),
+        RequestStatus::Matched => {
+            require!(
+                request_account.verification_key_hash.is_none()
+                    && request_account.escrow_amount == 0,
+                ErrorCode::MatchedRequestNotCancellable
+            );
+            Ok(())
+        }
+        _ => err!(ErrorCode::RequestNotCancellable),
+    }
+}
+
+// Check an account written under an older layout before it is grown. It may be too short
+// to decode as the current type, so the owner, discriminator and signer are read from
+// the raw data. Both resource and request accounts store the signer's key right after
+// their 8-byte id.
+fn check_migratable(account: &AccountInfo, discriminator: &[u8], signer: &Pubkey) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::AccountNotMigratable);
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 + 8 + 32 && data[..8] == *discriminator,
+        ErrorCode::AccountNotMigratable
+    );
+    require!(data[16..48] == signer.to_bytes(), ErrorCode::UnauthorizedMigration);
+    Ok(())
+}
+
+// Resize an account to `space` bytes with the payer topping up its rent. Fields are only
+// appended to account layouts, so an account written under an older layout is a prefix
+// of the current one and the zeroed tail decodes as the new fields' defaults.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    if account.data_len() < space {
+        account.realloc(space, true)?;
+    }
+    Ok(())
+}
+
+// Escrow goes back to the requester only once the request can no longer be paid out
+fn check_refundable(request_account: &ComputationRequest) -> Result<()> {
+    require!(
+        matches!(
+            request_account.status,
+            RequestStatus::Failed | RequestStatus::Cancelled
+        ),
+        ErrorCode::RequestNotRefundable
+    );
+    Ok(())
+}
+
+// Decode taxonomy values from their on-chain encodings, rejecting unknown codes
+fn parse_accelerator(code: u16) -> Result<AcceleratorModel> {
+    AcceleratorModel::try_from(code).map_err(|_| error!(ErrorCode::InvalidAccelerator))
+}
+
+fn parse_cpu_architecture(code: u8) -> Result<CpuArchitecture> {
+    CpuArchitecture::try_from(code).map_err(|_| error!(ErrorCode::InvalidCpuArchitecture))
+}
+
+fn parse_job_category(code: u8) -> Result<JobCategory> {
+    JobCategory::try_from(code).map_err(|_| error!(ErrorCode::InvalidJobCategory))
+}
+
+fn parse_region(code: [u8; 2]) -> Result<RegionCode> {
+    RegionCode::try_from(code).map_err(|_| error!(ErrorCode::InvalidRegionCode))
+}
+
+fn parse_resource_vector(vector: [u64; MAX_DIMENSIONS]) -> Result<ResourceVector> {
+    let vector = ResourceVector(vector);
+    vector
+        .validate()
+        .map_err(|_| error!(ErrorCode::InvalidResourceDimension))?;
+    Ok(vector)
+}
+
+fn parse_region_scope(code: [u8; 4]) -> Result<RegionScope> {
+    RegionScope::try_from(code).map_err(|_| error!(ErrorCode::InvalidRegionScope))
+}
+
+// Shorter measurements must be zero-padded so equal enclaves always compare equal
+fn parse_tee_measurement(
+    platform: u8,
+    measurement: &[u8; TEE_MEASUREMENT_LEN],
+) -> Result<TeePlatform> {
+    let platform =
+        TeePlatform::try_from(platform).map_err(|_| error!(ErrorCode::InvalidTeePlatform))?;
+    require!(
+        measurement[platform.measurement_len()..].iter().all(|&byte| byte == 0),
+        ErrorCode::InvalidTeeMeasurement
+    );
+    Ok(platform)
+}
+
+#[event]
+pub struct RequestClosed {
+    pub request_id: u64,
+    pub requester: Pubkey,
+    pub job_category: u8,
+    pub requirements: [u64; MAX_DIMENSIONS],
+    pub max_price_per_unit: u64,
+    pub duration_estimate: u64,
+    pub status: RequestStatus,
+    pub matched_resource: Option<Pubkey>,
+}
+
+#[event]
+pub struct ResourceRetired {
+    pub resource_id: u64,
+    pub provider: Pubkey,
+    pub accelerator: u16,
+    pub cpu_architecture: u8,
+    pub capacity: [u64; MAX_DIMENSIONS],
+    pub region: [u8; 2],
+    pub price_per_unit: u64,
+    pub reputation_score: i64,
+    pub total_usage_time: u64,
+}
+
+#[event]
+pub struct EncryptionKeyRotated {
+    pub resource_id: u64,
+    pub epoch: u32,
+    pub encryption_key: [u8; 32],
+}
+
+#[event]
+pub struct MatchOfferDeclined {
+    pub request_id: u64,
+    pub resource_id: u64,
+    pub expired: bool,
+    pub acceptance_rate_bps: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SystemState {
+    pub authority: Pubkey,
+    pub resource_count: u64,
+    pub computation_request_count: u64,
+    pub active_matches: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ResourceAccount {
+    pub resource_id: u64,
+    pub provider: Pubkey,
+    pub accelerator: u16,
+    pub cpu_architecture: u8,
+    pub capacity: [u64; MAX_DIMENSIONS],
+    pub allocated: [u64; MAX_DIMENSIONS],
+    pub capabilities: u128,
+    pub region: [u8; 2],
+    pub zone: u8,
+    pub price_per_unit: u64,
+    pub is_active: bool,
+    pub reputation_score: i64,
+    pub total_usage_time: u64,
+    pub active_matches: u64,
+    #[max_len(MAX_WORKER_KEYS)]
+    pub worker_keys: Vec<Pubkey>,
+    // X25519 keys for confidential job inputs. Epoch zero means none published yet.
+    pub encryption_key: [u8; 32],
+    pub encryption_key_epoch: u32,
+    pub previous_encryption_key: [u8; 32],
+    // Signed offers with a lower nonce have been revoked
+    pub min_offer_nonce: u64,
+    // Match offers the provider accepted, and those it rejected or let time out
+    pub offers_accepted: u64,
+    pub offers_declined: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ComputationRequest {
+    pub request_id: u64,
+    pub requester: Pubkey,
+    pub job_category: u8,
+    pub required_accelerator: Option<u16>,
+    pub required_cpu_architecture: Option<u8>,
+    pub requirements: [u64; MAX_DIMENSIONS],
+    pub required_capabilities: u128,
+    pub optional_capabilities: u128,
+    pub max_price_per_unit: u64,
+    pub preferred_region: Option<[u8; 2]>,
+    pub preferred_zone: u8,
+    pub residency: Option<[u8; 4]>,
+    pub duration_estimate: u64,
+    pub status: RequestStatus,
+    // Resource account holding the request's reservation
+    pub matched_resource: Option<Pubkey>,
+    pub result_hash: Option<[u8; 32]>,
+    pub verification_key_hash: Option<[u8; 32]>,
+    pub escrow_amount: u64,
+    pub required_tee: Option<TeeRequirement>,
+    pub agreed_price_per_unit: Option<u64>,
+    // Deadline for the provider to accept an offered match
+    pub offer_expires_at: i64,
+    // Resource that last rejected the request or let its offer time out
+    pub declined_resource: Option<Pubkey>,
+}
+
+// Parameters of a new computation request
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RequestArgs {
+    pub job_category: u8,
+    pub required_accelerator: Option<u16>,
+    pub required_cpu_architecture: Option<u8>,
+    pub requirements: [u64; MAX_DIMENSIONS],
+    pub required_capabilities: u128,
+    pub optional_capabilities: u128,
+    pub max_price_per_unit: u64,
+    pub preferred_region: Option<[u8; 2]>,
+    pub preferred_zone: u8,
+    pub residency: Option<[u8; 4]>,
+    pub duration_estimate: u64,
+    pub verification_key_hash: Option<[u8; 32]>,
+    pub required_tee: Option<TeeRequirement>,
+}
+
+// Signed offer terms, see calctra_contracts::offer for the signed message layout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OfferArgs {
+    pub price_per_unit: u64,
+    pub capacity: [u64; MAX_DIMENSIONS],
+    pub expiry: i64,
+    pub nonce: u64,
+}
+
+// Layout of one pair's remaining accounts in a batch match
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchPair {
+    pub has_benchmark: bool,
+    pub has_tee_attestation: bool,
+}
+
+// Indices of a matched request and resource among the participants of a stable matching
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StablePair {
+    pub request: u8,
+    pub resource: u8,
+}
+
+// Enclave a request must run in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct TeeRequirement {
+    pub platform: u8,
+    pub measurement: [u8; TEE_MEASUREMENT_LEN],
+}
+
+// A Groth16 verifying key over bn254, addressed by its hash
+#[account]
+#[derive(InitSpace)]
+pub struct VerificationKey {
+    pub hash: [u8; 32],
+    pub alpha_g1: [u8; G1_LEN],
+    pub beta_g2: [u8; G2_LEN],
+    pub gamma_g2: [u8; G2_LEN],
+    pub delta_g2: [u8; G2_LEN],
+    #[max_len(MAX_IC_POINTS)]
+    pub ic: Vec<[u8; G1_LEN]>,
+}
+
+// Marks a signed offer nonce as settled
+#[account]
+#[derive(InitSpace)]
+pub struct OfferReceipt {
+    pub resource: Pubkey,
+    pub nonce: u64,
+    pub request: Pubkey,
+}
+
+// A registered off-chain benchmark runner
+#[account]
+#[derive(InitSpace)]
+pub struct BenchmarkVerifier {
+    pub verifier: Pubkey,
+    pub is_active: bool,
+}
+
+// Normalized benchmark scores for a resource, attested by a registered verifier
+#[account]
+#[derive(InitSpace)]
+pub struct BenchmarkAttestation {
+    pub resource: Pubkey,
+    pub verifier: Pubkey,
+    pub fp64_gflops: u64,
+    pub memory_bandwidth_gbps: u64,
+    pub tensor_score: u64,
+    pub attested_at: i64,
+    pub expires_at: i64,
+}
+
+// A registered remote attestation service for trusted execution environments
+#[account]
+#[derive(InitSpace)]
+pub struct TeeVerifier {
+    pub verifier: Pubkey,
+    pub is_active: bool,
+}
+
+// Enclave measurement and platform of a resource, attested by a registered verifier
+#[account]
+#[derive(InitSpace)]
+pub struct TeeAttestation {
+    pub resource: Pubkey,
+    pub verifier: Pubkey,
+    pub platform: u8,
+    pub measurement: [u8; TEE_MEASUREMENT_LEN],
+    pub attested_at: i64,
+    pub expires_at: i64,
+}
+
+// Sealed-bid auction for a request. Deposits of bids that are never revealed are
+// forfeited to the requester.
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub request: Pubkey,
+    pub requester: Pubkey,
+    pub bid_deadline: i64,
+    pub reveal_deadline: i64,
+    pub deposit: u64,
+    pub bid_count: u32,
+    // Bids neither revealed nor forfeited yet
+    pub open_bids: u32,
+    pub best_price: Option<u64>,
+    pub best_resource: Option<Pubkey>,
+}
+
+// A committed bid. The account also holds the bidder's deposit.
+#[account]
+#[derive(InitSpace)]
+pub struct SealedBid {
+    pub auction: Pubkey,
+    pub resource: Pubkey,
+    pub provider: Pubkey,
+    pub commitment: [u8; 32],
+}
+
+// Ranked counterparts of a request or resource, most preferred first
+#[account]
+#[derive(InitSpace)]
+pub struct Preferences {
+    // Request or resource account the list belongs to
+    pub subject: Pubkey,
+    pub owner: Pubkey,
+    #[max_len(MAX_PREFERENCES)]
+    pub ranked: Vec<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum RequestStatus {
+    Pending,
+    Matched,
+    Completed,
+    Failed,
+    Cancelled,
+    Auctioning,
+    Offered,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SystemState::INIT_SPACE,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSystemAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump,
+        has_one = authority
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterResource<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(
+        init,
+        payer = provider,
+        space = 8 + ResourceAccount::INIT_SPACE
+    )]
+    pub resource_account: Account<'info, ResourceAccount>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateResource<'info> {
+    #[account(mut, has_one = provider)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    pub provider: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitComputationRequest<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + ComputationRequest::INIT_SPACE
+    )]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(request: RequestArgs, offer: OfferArgs)]
+pub struct SubmitWithOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + ComputationRequest::INIT_SPACE
+    )]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + OfferReceipt::INIT_SPACE,
+        seeds = [b"offer", resource_account.key().as_ref(), &offer.nonce.to_le_bytes()],
+        bump
+    )]
+    pub offer_receipt: Account<'info, OfferReceipt>,
+    // Required when the request sets benchmark thresholds
+    pub benchmark: Option<Account<'info, BenchmarkAttestation>>,
+    // Required when the request needs a trusted execution environment
+    pub tee_attestation: Option<Account<'info, TeeAttestation>>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    /// CHECK: Instructions sysvar, read to find the provider's Ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MatchResource<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    // Required when the request sets benchmark thresholds
+    pub benchmark: Option<Account<'info, BenchmarkAttestation>>,
+    // Required when the request needs a trusted execution environment
+    pub tee_attestation: Option<Account<'info, TeeAttestation>>,
+    pub matcher: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenAuction<'info> {
+    #[account(mut, has_one = requester)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [b"auction", request_account.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(has_one = provider)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    #[account(
+        init,
+        payer = provider,
+        space = 8 + SealedBid::INIT_SPACE,
+        seeds = [b"bid", auction.key().as_ref(), resource_account.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, SealedBid>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Update system state
-        system_state.active_matches -= 1;
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(address = auction.request)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(address = bid.resource)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    #[account(mut, has_one = auction, has_one = provider, close = provider)]
+    pub bid: Account<'info, SealedBid>,
+    // Required when the request sets benchmark thresholds
+    pub benchmark: Option<Account<'info, BenchmarkAttestation>>,
+    // Required when the request needs a trusted execution environment
+    pub tee_attestation: Option<Account<'info, TeeAttestation>>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+}
 
-        msg!("Computation for request {} completed with status: {}", 
-            request_id, 
-            if success { "success" } else { "failure" }
-        );
-        
-        Ok(())
-    }
+#[derive(Accounts)]
+pub struct ForfeitBid<'info> {
+    #[account(mut, has_one = requester)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, has_one = auction, close = requester)]
+    pub bid: Account<'info, SealedBid>,
+    /// CHECK: Requester of the auctioned request, receives the forfeited deposit
+    #[account(mut)]
+    pub requester: UncheckedAccount<'info>,
 }
 
-#[account]
-pub struct SystemState {
-    pub authority: Pubkey,
-    pub resource_count: u64,
-    pub computation_request_count: u64,
-    pub active_matches: u64,
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut, address = auction.request)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut, has_one = requester, close = requester)]
+    pub auction: Account<'info, Auction>,
+    // Required when the auction has a winning bid
+    #[account(mut)]
+    pub resource_account: Option<Account<'info, ResourceAccount>>,
+    pub benchmark: Option<Account<'info, BenchmarkAttestation>>,
+    pub tee_attestation: Option<Account<'info, TeeAttestation>>,
+    /// CHECK: Requester of the auctioned request, receives the auction account's rent
+    #[account(mut)]
+    pub requester: UncheckedAccount<'info>,
 }
 
-#[account]
-pub struct ResourceAccount {
-    pub resource_id: u64,
-    pub provider: Pubkey,
-    pub resource_type: String,
-    pub computation_power: u64,
-    pub available_memory: u64,
-    pub location: String,
-    pub price_per_unit: u64,
-    pub is_active: bool,
-    pub reputation_score: i64,
-    pub total_usage_time: u64,
+#[derive(Accounts)]
+pub struct BatchMatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub matcher: Signer<'info>,
 }
 
-#[account]
-pub struct ComputationRequest {
-    pub request_id: u64,
-    pub requester: Pubkey,
-    pub computation_type: String,
-    pub required_power: u64,
-    pub required_memory: u64,
-    pub max_price_per_unit: u64,
-    pub preferred_location: Option<String>,
-    pub duration_estimate: u64,
-    pub status: RequestStatus,
-    pub matched_resource: Option<u64>,
+#[derive(Accounts)]
+pub struct AcceptMatch<'info> {
+    #[account(mut)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut, has_one = provider)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    pub provider: Signer<'info>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum RequestStatus {
-    Pending,
-    Matched,
-    Completed,
-    Failed,
-    Cancelled,
+#[derive(Accounts)]
+pub struct RejectMatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut, has_one = provider)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    pub provider: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = 8 + 32 + 8 + 8 + 8)]
+pub struct ExpireMatchOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
     pub system_state: Account<'info, SystemState>,
     #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut)]
+    pub resource_account: Account<'info, ResourceAccount>,
 }
 
 #[derive(Accounts)]
-pub struct RegisterResource<'info> {
+pub struct SetRequestPreferences<'info> {
+    #[account(has_one = requester)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(
+        init_if_needed,
+        payer = requester,
+        space = 8 + Preferences::INIT_SPACE,
+        seeds = [b"preferences", request_account.key().as_ref()],
+        bump
+    )]
+    pub preferences: Account<'info, Preferences>,
     #[account(mut)]
-    pub system_state: Account<'info, SystemState>,
+    pub requester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetResourcePreferences<'info> {
+    #[account(has_one = provider)]
+    pub resource_account: Account<'info, ResourceAccount>,
     #[account(
-        init,
+        init_if_needed,
         payer = provider,
-        space = 8 + 8 + 32 + 100 + 8 + 8 + 100 + 8 + 1 + 8 + 8
+        space = 8 + Preferences::INIT_SPACE,
+        seeds = [b"preferences", resource_account.key().as_ref()],
+        bump
     )]
-    pub resource_account: Account<'info, ResourceAccount>,
+    pub preferences: Account<'info, Preferences>,
     #[account(mut)]
     pub provider: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SubmitComputationRequest<'info> {
+pub struct ClosePreferences<'info> {
+    #[account(mut, has_one = owner, close = owner)]
+    pub preferences: Account<'info, Preferences>,
     #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteComputation<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
     pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: Instructions sysvar, read to find the worker's Ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(hash: [u8; 32])]
+pub struct RegisterVerificationKey<'info> {
     #[account(
         init,
-        payer = requester,
-        space = 8 + 8 + 32 + 100 + 8 + 8 + 8 + 100 + 8 + 4 + 8
+        payer = payer,
+        space = 8 + VerificationKey::INIT_SPACE,
+        seeds = [b"verification_key", hash.as_ref()],
+        bump
     )]
+    pub verification_key: Account<'info, VerificationKey>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundRequest<'info> {
+    #[account(mut, has_one = requester)]
     pub request_account: Account<'info, ComputationRequest>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = requester,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program,
+        seeds = [b"escrow", request_account.key().as_ref()],
+        bump
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = requester_token_account.mint == mint.key())]
+    pub requester_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub requester: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct MatchResource<'info> {
-    #[account(mut)]
+pub struct CompleteWithProof<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
     pub system_state: Account<'info, SystemState>,
     #[account(mut)]
     pub request_account: Account<'info, ComputationRequest>,
+    #[account(mut, has_one = provider)]
+    pub resource_account: Account<'info, ResourceAccount>,
+    #[account(
+        constraint = request_account.verification_key_hash == Some(verification_key.hash)
+            @ ErrorCode::VerificationKeyMismatch
+    )]
+    pub verification_key: Account<'info, VerificationKey>,
+    // Required when the request has escrowed payment
+    #[account(
+        mut,
+        seeds = [b"escrow", request_account.key().as_ref()],
+        bump
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, constraint = provider_token_account.owner == provider.key())]
+    pub provider_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub provider: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RefundEscrow<'info> {
+    #[account(mut, has_one = requester)]
+    pub request_account: Account<'info, ComputationRequest>,
+    #[account(
+        mut,
+        seeds = [b"escrow", request_account.key().as_ref()],
+        bump
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = escrow.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, constraint = requester_token_account.mint == escrow.mint)]
+    pub requester_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub requester: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AddVerifier<'info> {
+    #[account(
+        seeds = [b"system_state"],
+        bump,
+        has_one = authority
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BenchmarkVerifier::INIT_SPACE,
+        seeds = [b"verifier", verifier_key.key().as_ref()],
+        bump
+    )]
+    pub verifier: Account<'info, BenchmarkVerifier>,
+    /// CHECK: Public key of the benchmark runner being registered
+    pub verifier_key: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveVerifier<'info> {
+    #[account(
+        seeds = [b"system_state"],
+        bump,
+        has_one = authority
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut, close = authority)]
+    pub verifier: Account<'info, BenchmarkVerifier>,
     #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitBenchmark<'info> {
     pub resource_account: Account<'info, ResourceAccount>,
-    pub matcher: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = verifier_key,
+        space = 8 + BenchmarkAttestation::INIT_SPACE,
+        seeds = [b"benchmark", resource_account.key().as_ref()],
+        bump
+    )]
+    pub benchmark: Account<'info, BenchmarkAttestation>,
+    #[account(
+        seeds = [b"verifier", verifier_key.key().as_ref()],
+        bump
+    )]
+    pub verifier: Account<'info, BenchmarkVerifier>,
+    #[account(mut)]
+    pub verifier_key: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CompleteComputation<'info> {
+pub struct AddTeeVerifier<'info> {
+    #[account(
+        seeds = [b"system_state"],
+        bump,
+        has_one = authority
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TeeVerifier::INIT_SPACE,
+        seeds = [b"tee_verifier", verifier_key.key().as_ref()],
+        bump
+    )]
+    pub verifier: Account<'info, TeeVerifier>,
+    /// CHECK: Public key of the attestation service being registered
+    pub verifier_key: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveTeeVerifier<'info> {
+    #[account(
+        seeds = [b"system_state"],
+        bump,
+        has_one = authority
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut, close = authority)]
+    pub verifier: Account<'info, TeeVerifier>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitTeeAttestation<'info> {
+    pub resource_account: Account<'info, ResourceAccount>,
+    #[account(
+        init_if_needed,
+        payer = verifier_key,
+        space = 8 + TeeAttestation::INIT_SPACE,
+        seeds = [b"tee_attestation", resource_account.key().as_ref()],
+        bump
+    )]
+    pub tee_attestation: Account<'info, TeeAttestation>,
+    #[account(
+        seeds = [b"tee_verifier", verifier_key.key().as_ref()],
+        bump
+    )]
+    pub verifier: Account<'info, TeeVerifier>,
     #[account(mut)]
+    pub verifier_key: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRequest<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump
+    )]
     pub system_state: Account<'info, SystemState>,
+    #[account(mut, has_one = requester)]
+    pub request_account: Account<'info, ComputationRequest>,
+    // Required when the request has already been matched
+    #[account(mut)]
+    pub resource_account: Option<Account<'info, ResourceAccount>>,
+    pub requester: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateResource<'info> {
+    /// CHECK: Resource account in an older layout, checked by hand since it may not decode
+    #[account(mut)]
+    pub resource_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRequest<'info> {
+    /// CHECK: Request account in an older layout, checked by hand since it may not decode
     #[account(mut)]
+    pub request_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRequest<'info> {
+    #[account(mut, has_one = requester, close = requester)]
     pub request_account: Account<'info, ComputationRequest>,
+    /// CHECK: Escrow vault of the request, which only exists once the request was funded
+    #[account(
+        mut,
+        seeds = [b"escrow", request_account.key().as_ref()],
+        bump
+    )]
+    pub escrow: UncheckedAccount<'info>,
     #[account(mut)]
+    pub requester: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RetireResource<'info> {
+    #[account(mut, has_one = provider, close = provider)]
     pub resource_account: Account<'info, ResourceAccount>,
-    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub provider: Signer<'info>,
 }
 
 #[error_code]
@@ -325,4 +2891,223 @@ pub enum ErrorCode {
     RequestNotMatched,
     #[msg("Resource mismatch")]
     ResourceMismatch,
-} 
\ No newline at end of file
+    #[msg("Request is not in a terminal status")]
+    RequestNotTerminal,
+    #[msg("Resource still has active matches")]
+    ResourceHasActiveMatches,
+    #[msg("Resource is already active")]
+    ResourceAlreadyActive,
+    #[msg("Unknown accelerator model")]
+    InvalidAccelerator,
+    #[msg("Unknown CPU architecture")]
+    InvalidCpuArchitecture,
+    #[msg("Unknown job category")]
+    InvalidJobCategory,
+    #[msg("Unknown ISO-3166 region code")]
+    InvalidRegionCode,
+    #[msg("Resource accelerator does not match the request")]
+    AcceleratorMismatch,
+    #[msg("Resource CPU architecture does not match the request")]
+    CpuArchitectureMismatch,
+    #[msg("Invalid region scope")]
+    InvalidRegionScope,
+    #[msg("Resource location violates the request's data residency constraint")]
+    ResidencyViolation,
+    #[msg("Resource vector uses an unregistered dimension")]
+    InvalidResourceDimension,
+    #[msg("Resource has insufficient capacity")]
+    InsufficientCapacity,
+    #[msg("Capacity cannot shrink below the allocated amount")]
+    CapacityBelowAllocation,
+    #[msg("Released more capacity than was allocated")]
+    AllocationUnderflow,
+    #[msg("Request cannot be cancelled in its current status")]
+    RequestNotCancellable,
+    #[msg("Resource is missing a required capability")]
+    MissingCapability,
+    #[msg("Signer is not an active benchmark verifier")]
+    UnauthorizedVerifier,
+    #[msg("Benchmark attestation has expired")]
+    BenchmarkExpired,
+    #[msg("Benchmark attestation belongs to a different resource")]
+    BenchmarkMismatch,
+    #[msg("Attested benchmark score is below the requirement")]
+    InsufficientBenchmarkScore,
+    #[msg("Resource already has the maximum number of worker keys")]
+    TooManyWorkerKeys,
+    #[msg("Worker key is already enrolled")]
+    WorkerKeyAlreadyEnrolled,
+    #[msg("Worker key is not enrolled for this resource")]
+    WorkerKeyNotFound,
+    #[msg("Provider completion requires a worker result attestation")]
+    MissingResultAttestation,
+    #[msg("Invalid worker result attestation")]
+    InvalidResultAttestation,
+    #[msg("Result attestation was not signed by an enrolled worker key")]
+    UnknownWorkerKey,
+    #[msg("Invalid Groth16 verification key")]
+    InvalidVerificationKey,
+    #[msg("Verification key does not hash to the given value")]
+    VerificationKeyHashMismatch,
+    #[msg("Verification key is not the one registered by the request")]
+    VerificationKeyMismatch,
+    #[msg("Verifiable requests must be completed with a proof")]
+    ProofRequired,
+    #[msg("Groth16 proof verification failed")]
+    ProofVerificationFailed,
+    #[msg("Request can no longer be funded")]
+    RequestNotFundable,
+    #[msg("Request escrow can only be refunded after failure or cancellation")]
+    RequestNotRefundable,
+    #[msg("Escrow and provider token accounts are required to release payment")]
+    EscrowRequired,
+    #[msg("Request escrow must be paid out or refunded before closing")]
+    EscrowNotEmpty,
+    #[msg("Unknown TEE platform")]
+    InvalidTeePlatform,
+    #[msg("TEE measurement is not valid for its platform")]
+    InvalidTeeMeasurement,
+    #[msg("Request requires a TEE attestation")]
+    TeeRequired,
+    #[msg("TEE attestation belongs to a different resource")]
+    TeeAttestationMismatch,
+    #[msg("TEE attestation has expired")]
+    TeeAttestationExpired,
+    #[msg("Resource enclave does not match the required measurement")]
+    TeeMeasurementMismatch,
+    #[msg("Encryption key must be non-zero and differ from the current key")]
+    InvalidEncryptionKey,
+    #[msg("Auction bidding and reveal periods must be positive")]
+    InvalidAuctionPeriod,
+    #[msg("Auction bidding period has ended")]
+    BiddingClosed,
+    #[msg("Auction is not in its reveal period")]
+    RevealClosed,
+    #[msg("Auction reveal period has not ended")]
+    AuctionNotEnded,
+    #[msg("Revealed price and salt do not match the commitment")]
+    InvalidBidReveal,
+    #[msg("Every bid must be revealed or forfeited before settlement")]
+    UnresolvedBids,
+    #[msg("Request is not being auctioned")]
+    RequestNotAuctioning,
+    #[msg("Signed offer has expired")]
+    OfferExpired,
+    #[msg("Signed offer has been revoked")]
+    OfferRevoked,
+    #[msg("Offer is not signed by the resource's provider")]
+    InvalidOfferSignature,
+    #[msg("Request needs more capacity than the offer sells")]
+    OfferCapacityExceeded,
+    #[msg("Offer nonce floor can only increase")]
+    InvalidOfferNonce,
+    #[msg("Batch must hold between one and the maximum number of pairs")]
+    InvalidBatchSize,
+    #[msg("Request appears more than once in the batch")]
+    DuplicateBatchRequest,
+    #[msg("Batch account must be writable")]
+    AccountNotWritable,
+    #[msg("One or more pairs in the batch were rejected")]
+    BatchMatchFailed,
+    #[msg("Preference list is too long or ranks an entry twice")]
+    InvalidPreferences,
+    #[msg("Preferences account does not belong to the participant")]
+    PreferencesMismatch,
+    #[msg("Matching must pair eligible participants at most once each")]
+    InvalidStableMatching,
+    #[msg("Matching leaves a request and resource that prefer each other unmatched")]
+    BlockingPair,
+    #[msg("Request is not waiting for a provider to accept a match")]
+    RequestNotOffered,
+    #[msg("Match offer has expired")]
+    MatchOfferExpired,
+    #[msg("Match offer has not expired yet")]
+    MatchOfferNotExpired,
+    #[msg("Resource already declined this request")]
+    MatchDeclined,
+    #[msg("Only requests settled by a proof can escrow payment")]
+    EscrowRequiresProof,
+    #[msg("Request has benchmark requirements and needs the resource's benchmark attestation")]
+    BenchmarkRequired,
+    #[msg("A matched request paid through escrow can only end by proof or failure")]
+    MatchedRequestNotCancellable,
+    #[msg("Account is not a resource or request account of this program")]
+    AccountNotMigratable,
+    #[msg("Only the account's provider or requester can migrate it")]
+    UnauthorizedMigration,
+    #[msg("Token program does not own the request's escrow vault")]
+    EscrowProgramMismatch,
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(status: RequestStatus) -> ComputationRequest {
+        ComputationRequest {
+            request_id: 1,
+            requester: Pubkey::new_unique(),
+            job_category: 0,
+            required_accelerator: None,
+            required_cpu_architecture: None,
+            requirements: [0; MAX_DIMENSIONS],
+            required_capabilities: 0,
+            optional_capabilities: 0,
+            max_price_per_unit: 0,
+            preferred_region: None,
+            preferred_zone: 0,
+            residency: None,
+            duration_estimate: 0,
+            status,
+            matched_resource: None,
+            result_hash: None,
+            verification_key_hash: None,
+            escrow_amount: 0,
+            required_tee: None,
+            agreed_price_per_unit: None,
+            offer_expires_at: 0,
+            declined_resource: None,
+        }
+    }
+
+    #[test]
+    fn test_matched_verifiable_request_cannot_be_cancelled_and_refunded() {
+        let mut matched = request(RequestStatus::Matched);
+        matched.matched_resource = Some(Pubkey::new_unique());
+        matched.verification_key_hash = Some([7; 32]);
+        matched.escrow_amount = 1_000;
+
+        // The cancel fails, so the request never reaches a status the escrow refunds from
+        assert!(check_cancellable(&matched).is_err());
+        assert!(check_refundable(&matched).is_err());
+
+        // Not yet funded, the key alone still keeps the request with its provider
+        matched.escrow_amount = 0;
+        assert!(check_cancellable(&matched).is_err());
+
+        // Only a failure reported by the system authority releases the escrow
+        matched.status = RequestStatus::Failed;
+        assert!(check_refundable(&matched).is_ok());
+    }
+
+    #[test]
+    fn test_unclaimed_funded_request_can_be_cancelled_and_refunded() {
+        for status in [RequestStatus::Pending, RequestStatus::Offered] {
+            let mut funded = request(status);
+            funded.verification_key_hash = Some([7; 32]);
+            funded.escrow_amount = 1_000;
+
+            assert!(check_cancellable(&funded).is_ok());
+            assert!(check_refundable(&funded).is_err());
+            funded.status = RequestStatus::Cancelled;
+            assert!(check_refundable(&funded).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_matched_unpaid_request_can_be_cancelled() {
+        assert!(check_cancellable(&request(RequestStatus::Matched)).is_ok());
+        assert!(check_cancellable(&request(RequestStatus::Completed)).is_err());
+        assert!(check_cancellable(&request(RequestStatus::Cancelled)).is_err());
+    }
+}
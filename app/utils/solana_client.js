@@ -3,9 +3,28 @@
  * Handles interaction with the Solana blockchain for the Calctra platform
  */
 
-const { Connection, PublicKey, Transaction, sendAndConfirmTransaction } = require('@solana/web3.js');
+const crypto = require('crypto');
+const {
+    AddressLookupTableProgram,
+    Connection,
+    PublicKey,
+    Transaction,
+    TransactionInstruction,
+    TransactionMessage,
+    VersionedTransaction,
+    sendAndConfirmTransaction
+} = require('@solana/web3.js');
 const { Token, TOKEN_PROGRAM_ID } = require('@solana/spl-token');
 
+// Must match MAX_BATCH_PAIRS in the resource matching program
+const MAX_BATCH_PAIRS = 8;
+
+// Addresses added per extendLookupTable instruction
+const LOOKUP_TABLE_EXTEND_CHUNK = 20;
+
+// Delay between slot polls, about one slot
+const SLOT_POLL_INTERVAL_MS = 400;
+
 /**
  * SolanaClient provides utility methods for interacting with Solana blockchain
  * and Calctra's smart contracts
@@ -166,6 +185,121 @@ class SolanaClient {
         }
     }
 
+    /**
+     * Create an address lookup table holding the accounts of a batch match, so large
+     * batches fit in a single transaction
+     * @param {Object} wallet - The wallet paying for and owning the lookup table
+     * @param {Array<PublicKey>} addresses - Request, resource and attestation accounts to store
+     * @returns {Promise<PublicKey>} Address of the lookup table
+     */
+    async createMatchingLookupTable(wallet, addresses) {
+        try {
+            const slot = await this.connection.getSlot('finalized');
+            const [createInstruction, lookupTableAddress] = AddressLookupTableProgram.createLookupTable({
+                authority: wallet.publicKey,
+                payer: wallet.publicKey,
+                recentSlot: slot
+            });
+
+            await sendAndConfirmTransaction(this.connection, new Transaction().add(createInstruction), [wallet]);
+
+            // Addresses are added in chunks so each extend transaction stays under the size limit
+            for (let i = 0; i < addresses.length; i += LOOKUP_TABLE_EXTEND_CHUNK) {
+                const extendInstruction = AddressLookupTableProgram.extendLookupTable({
+                    lookupTable: lookupTableAddress,
+                    authority: wallet.publicKey,
+                    payer: wallet.publicKey,
+                    addresses: addresses.slice(i, i + LOOKUP_TABLE_EXTEND_CHUNK)
+                });
+                await sendAndConfirmTransaction(this.connection, new Transaction().add(extendInstruction), [wallet]);
+            }
+
+            // Addresses only become usable in the slot after they were added
+            const extendedSlot = await this.connection.getSlot('confirmed');
+            await this._waitForSlot(extendedSlot + 1);
+            return lookupTableAddress;
+        } catch (error) {
+            console.error('Error creating matching lookup table:', error);
+            throw error;
+        }
+    }
+
+    /**
+     * Match many requests with resources in one all-or-nothing transaction
+     * @param {Object} wallet - The matcher wallet, either the system authority or the provider of every resource
+     * @param {PublicKey} systemState - The system state account
+     * @param {Array<Object>} pairs - Pairs of { request, resource, benchmark?, teeAttestation? } account addresses
+     * @param {PublicKey} [lookupTableAddress] - Lookup table holding the pair accounts
+     * @returns {Promise<string>} Transaction signature
+     */
+    async batchMatch(wallet, systemState, pairs, lookupTableAddress) {
+        try {
+            if (pairs.length === 0 || pairs.length > MAX_BATCH_PAIRS) {
+                throw new Error(`Batch must hold between 1 and ${MAX_BATCH_PAIRS} pairs`);
+            }
+
+            const keys = [
+                { pubkey: systemState, isSigner: false, isWritable: true },
+                { pubkey: wallet.publicKey, isSigner: true, isWritable: false }
+            ];
+            for (const pair of pairs) {
+                keys.push({ pubkey: pair.request, isSigner: false, isWritable: true });
+                keys.push({ pubkey: pair.resource, isSigner: false, isWritable: true });
+                if (pair.benchmark) {
+                    keys.push({ pubkey: pair.benchmark, isSigner: false, isWritable: false });
+                }
+                if (pair.teeAttestation) {
+                    keys.push({ pubkey: pair.teeAttestation, isSigner: false, isWritable: false });
+                }
+            }
+
+            // Borsh layout of Vec<BatchPair>: u32 length, then one flag byte per optional account
+            const args = Buffer.alloc(4 + pairs.length * 2);
+            args.writeUInt32LE(pairs.length, 0);
+            pairs.forEach((pair, i) => {
+                args.writeUInt8(pair.benchmark ? 1 : 0, 4 + i * 2);
+                args.writeUInt8(pair.teeAttestation ? 1 : 0, 5 + i * 2);
+            });
+
+            const instruction = new TransactionInstruction({
+                keys,
+                programId: this.programId,
+                data: Buffer.concat([this._instructionDiscriminator('batch_match'), args])
+            });
+
+            const lookupTables = [];
+            if (lookupTableAddress) {
+                const lookupTable = await this.connection.getAddressLookupTable(lookupTableAddress);
+                if (!lookupTable.value) {
+                    throw new Error(`Lookup table ${lookupTableAddress.toBase58()} not found`);
+                }
+                lookupTables.push(lookupTable.value);
+            }
+
+            const { blockhash, lastValidBlockHeight } = await this.connection.getLatestBlockhash();
+            const message = new TransactionMessage({
+                payerKey: wallet.publicKey,
+                recentBlockhash: blockhash,
+                instructions: [instruction]
+            }).compileToV0Message(lookupTables);
+
+            const transaction = new VersionedTransaction(message);
+            transaction.sign([wallet]);
+
+            const signature = await this.connection.sendTransaction(transaction);
+            await this.connection.confirmTransaction({ signature, blockhash, lastValidBlockHeight });
+
+            return signature;
+        } catch (error) {
+            // Program logs name every rejected pair and the reason
+            if (error.logs) {
+                console.error('Batch match logs:', error.logs);
+            }
+            console.error('Error batch matching resources:', error);
+            throw error;
+        }
+    }
+
     /**
      * Complete a computation task
      * @param {Object} wallet - The wallet to use for transactions
@@ -274,6 +408,28 @@ class SolanaClient {
         }
     }
 
+    /**
+     * Wait until the cluster has reached a slot
+     * @private
+     * @param {number} slot - The slot to wait for
+     * @returns {Promise<void>}
+     */
+    async _waitForSlot(slot) {
+        while (await this.connection.getSlot('confirmed') < slot) {
+            await new Promise(resolve => setTimeout(resolve, SLOT_POLL_INTERVAL_MS));
+        }
+    }
+
+    /**
+     * Compute the Anchor discriminator of a program instruction
+     * @private
+     * @param {string} name - The instruction name in snake case
+     * @returns {Buffer} The first 8 bytes of sha256("global:<name>")
+     */
+    _instructionDiscriminator(name) {
+        return crypto.createHash('sha256').update(`global:${name}`).digest().subarray(0, 8);
+    }
+
     /**
      * Create a transaction with the given instruction
      * @private
@@ -0,0 +1,245 @@
+// Off-chain mirrors of the accounts the Anchor resource matching program
+// (app/solana-contracts/resource_matching.rs) stores. Anchor prefixes each account
+// with the first 8 bytes of sha256("account:<Name>") and Borsh-encodes the fields in
+// declaration order, so the field order here must follow the program exactly. Accounts
+// are allocated at their largest size, and the unused tail is ignored when decoding.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::hash::hash;
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::resource_vector::{ResourceVector, MAX_DIMENSIONS};
+use crate::taxonomy::TEE_MEASUREMENT_LEN;
+
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+// An account type of the Anchor program, identified by its discriminator
+pub trait AnchorAccount: BorshSerialize + BorshDeserialize {
+    // Struct name in the program, which the discriminator is derived from
+    const NAME: &'static str;
+
+    fn discriminator() -> [u8; DISCRIMINATOR_LEN] {
+        let mut discriminator = [0u8; DISCRIMINATOR_LEN];
+        discriminator
+            .copy_from_slice(&hash(format!("account:{}", Self::NAME).as_bytes()).to_bytes()[..8]);
+        discriminator
+    }
+
+    // Decode raw account data, rejecting accounts of any other type
+    fn decode(data: &[u8]) -> Result<Self, AccountDecodeError> {
+        if data.len() < DISCRIMINATOR_LEN {
+            return Err(AccountDecodeError::InvalidData);
+        }
+        let (discriminator, mut fields) = data.split_at(DISCRIMINATOR_LEN);
+        if discriminator != Self::discriminator() {
+            return Err(AccountDecodeError::WrongAccountType);
+        }
+        Self::deserialize(&mut fields).map_err(|_| AccountDecodeError::InvalidData)
+    }
+
+    // Account data as the program writes it, without the unused tail
+    fn encode(&self) -> Vec<u8> {
+        let mut data = Self::discriminator().to_vec();
+        data.extend(self.try_to_vec().unwrap());
+        data
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RequestStatus {
+    #[default]
+    Pending,
+    Matched,
+    Completed,
+    Failed,
+    Cancelled,
+    Auctioning,
+    Offered,
+}
+
+// Enclave a request must run in
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TeeRequirement {
+    pub platform: u8,
+    pub measurement: [u8; TEE_MEASUREMENT_LEN],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceAccount {
+    pub resource_id: u64,
+    pub provider: Pubkey,
+    pub accelerator: u16,
+    pub cpu_architecture: u8,
+    pub capacity: [u64; MAX_DIMENSIONS],
+    pub allocated: [u64; MAX_DIMENSIONS],
+    pub capabilities: u128,
+    pub region: [u8; 2],
+    pub zone: u8,
+    pub price_per_unit: u64,
+    pub is_active: bool,
+    pub reputation_score: i64,
+    pub total_usage_time: u64,
+    pub active_matches: u64,
+    pub worker_keys: Vec<Pubkey>,
+    pub encryption_key: [u8; 32],
+    pub encryption_key_epoch: u32,
+    pub previous_encryption_key: [u8; 32],
+    pub min_offer_nonce: u64,
+    pub offers_accepted: u64,
+    pub offers_declined: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComputationRequest {
+    pub request_id: u64,
+    pub requester: Pubkey,
+    pub job_category: u8,
+    pub required_accelerator: Option<u16>,
+    pub required_cpu_architecture: Option<u8>,
+    pub requirements: [u64; MAX_DIMENSIONS],
+    pub required_capabilities: u128,
+    pub optional_capabilities: u128,
+    pub max_price_per_unit: u64,
+    pub preferred_region: Option<[u8; 2]>,
+    pub preferred_zone: u8,
+    pub residency: Option<[u8; 4]>,
+    pub duration_estimate: u64,
+    pub status: RequestStatus,
+    pub matched_resource: Option<Pubkey>,
+    pub result_hash: Option<[u8; 32]>,
+    pub verification_key_hash: Option<[u8; 32]>,
+    pub escrow_amount: u64,
+    pub required_tee: Option<TeeRequirement>,
+    pub agreed_price_per_unit: Option<u64>,
+    pub offer_expires_at: i64,
+    pub declined_resource: Option<Pubkey>,
+}
+
+impl AnchorAccount for ResourceAccount {
+    const NAME: &'static str = "ResourceAccount";
+}
+
+impl AnchorAccount for ComputationRequest {
+    const NAME: &'static str = "ComputationRequest";
+}
+
+impl ResourceAccount {
+    // Capacity not yet reserved by matches, or None if the account is over-allocated
+    pub fn free_capacity(&self) -> Option<ResourceVector> {
+        ResourceVector(self.capacity).checked_sub(&ResourceVector(self.allocated))
+    }
+}
+
+// Account decoding errors
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum AccountDecodeError {
+    #[error("Account discriminator does not match the expected account type")]
+    WrongAccountType,
+    #[error("Account data is too short or malformed")]
+    InvalidData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_SOURCE: &str = include_str!("../../app/solana-contracts/resource_matching.rs");
+    const MIRROR_SOURCE: &str = include_str!("accounts.rs");
+
+    // Field or variant lines of a type declared in Rust source, without comments and
+    // attributes
+    fn declared_members(source: &str, kind: &str, name: &str) -> Vec<String> {
+        let header = format!("\npub {} {} {{\n", kind, name);
+        let (_, body) = source
+            .split_once(&header)
+            .unwrap_or_else(|| panic!("{} {} is not declared", kind, name));
+        body.lines()
+            .map(str::trim)
+            .take_while(|line| *line != "}")
+            .filter(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_layouts_follow_the_program() {
+        for (kind, name) in [
+            ("struct", "ResourceAccount"),
+            ("struct", "ComputationRequest"),
+            ("struct", "TeeRequirement"),
+            ("enum", "RequestStatus"),
+        ] {
+            let program = declared_members(PROGRAM_SOURCE, kind, name);
+            assert!(!program.is_empty());
+            assert_eq!(
+                declared_members(MIRROR_SOURCE, kind, name),
+                program,
+                "{} no longer matches the program",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_discriminators_follow_anchor() {
+        assert_eq!(
+            ResourceAccount::discriminator(),
+            hash(b"account:ResourceAccount").to_bytes()[..8]
+        );
+        assert_ne!(
+            ResourceAccount::discriminator(),
+            ComputationRequest::discriminator()
+        );
+    }
+
+    #[test]
+    fn test_decode_ignores_unused_tail() {
+        let request = ComputationRequest {
+            request_id: 7,
+            requester: Pubkey::new_unique(),
+            required_accelerator: Some(3),
+            status: RequestStatus::Offered,
+            declined_resource: Some(Pubkey::new_unique()),
+            ..ComputationRequest::default()
+        };
+        let mut data = request.encode();
+        data.resize(data.len() + 256, 0);
+
+        assert_eq!(ComputationRequest::decode(&data), Ok(request));
+    }
+
+    #[test]
+    fn test_decode_rejects_other_accounts() {
+        let resource = ResourceAccount {
+            worker_keys: vec![Pubkey::new_unique()],
+            ..ResourceAccount::default()
+        };
+        let data = resource.encode();
+
+        assert_eq!(ResourceAccount::decode(&data), Ok(resource));
+        assert_eq!(
+            ComputationRequest::decode(&data),
+            Err(AccountDecodeError::WrongAccountType)
+        );
+        assert_eq!(
+            ResourceAccount::decode(&data[..DISCRIMINATOR_LEN - 1]),
+            Err(AccountDecodeError::InvalidData)
+        );
+        assert_eq!(
+            ResourceAccount::decode(&data[..DISCRIMINATOR_LEN + 40]),
+            Err(AccountDecodeError::InvalidData)
+        );
+    }
+
+    #[test]
+    fn test_free_capacity() {
+        let mut resource = ResourceAccount::default();
+        resource.capacity[0] = 8;
+        resource.allocated[0] = 3;
+        assert_eq!(resource.free_capacity().map(|free| free.0[0]), Some(5));
+
+        resource.allocated[0] = 9;
+        assert_eq!(resource.free_capacity(), None);
+    }
+}
@@ -0,0 +1,372 @@
+// Worker-signed result attestations shared by the native and Anchor matching programs.
+// A worker signs (request, result hash, runtime) with its enrolled Ed25519 key. The
+// signature is checked by the Ed25519 native program in the instruction immediately
+// before completion, and the matching program inspects that instruction through the
+// instructions sysvar to confirm which key signed which message.
+
+use solana_program::{
+    account_info::AccountInfo,
+    ed25519_program,
+    pubkey::Pubkey,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use thiserror::Error;
+
+// Number of worker keys a resource can enroll
+pub const MAX_WORKER_KEYS: usize = 4;
+
+// Domain separator so an attestation signature cannot be replayed as any other message
+pub const ATTESTATION_DOMAIN: &[u8; 16] = b"calctra-result-1";
+
+// Serialized attestation message length: domain, request, result hash, runtime
+pub const ATTESTATION_MESSAGE_LEN: usize = 16 + 32 + 32 + 8;
+
+// Layout of the Ed25519 program instruction data
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+// Instruction index the Ed25519 program uses for "data in this same instruction"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+// The statement a worker signs when reporting a result.
+//
+// The request is identified by its account address rather than its counter ID, since
+// counters restart for every system state and would let a signature be replayed
+// against a different request with the same number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResultAttestation {
+    pub request: Pubkey,
+    pub result_hash: [u8; 32],
+    pub runtime: u64,
+}
+
+impl ResultAttestation {
+    pub fn message(&self) -> [u8; ATTESTATION_MESSAGE_LEN] {
+        let mut message = [0u8; ATTESTATION_MESSAGE_LEN];
+        message[..16].copy_from_slice(ATTESTATION_DOMAIN);
+        message[16..48].copy_from_slice(self.request.as_ref());
+        message[48..80].copy_from_slice(&self.result_hash);
+        message[80..].copy_from_slice(&self.runtime.to_le_bytes());
+        message
+    }
+
+    // Confirm that the instruction before the current one verifies a signature over
+    // exactly this attestation, and return the signing key
+    pub fn verify_preceding(
+        &self,
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<Pubkey, AttestationError> {
+        preceding_ed25519_signer(instructions_sysvar, &self.message())
+    }
+}
+
+// Confirm that the instruction before the current one is an Ed25519 program
+// instruction verifying a signature over the given message, and return the signing key
+pub fn preceding_ed25519_signer(
+    instructions_sysvar: &AccountInfo,
+    expected_message: &[u8],
+) -> Result<Pubkey, AttestationError> {
+    let current = load_current_index_checked(instructions_sysvar)
+        .map_err(|_| AttestationError::MissingSignature)?;
+    let index = current
+        .checked_sub(1)
+        .ok_or(AttestationError::MissingSignature)?;
+    let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)
+        .map_err(|_| AttestationError::MissingSignature)?;
+    if instruction.program_id != ed25519_program::ID {
+        return Err(AttestationError::MissingSignature);
+    }
+
+    let (signer, message) = parse_single_signature(&instruction.data)?;
+    if message != expected_message {
+        return Err(AttestationError::MessageMismatch);
+    }
+    Ok(signer)
+}
+
+// Extract the public key and message from an Ed25519 program instruction that checks
+// a single signature with all data embedded in the instruction itself
+fn parse_single_signature(data: &[u8]) -> Result<(Pubkey, &[u8]), AttestationError> {
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        return Err(AttestationError::MalformedSignatureInstruction);
+    }
+
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN];
+    let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+    let signature_offset = read_u16(0) as usize;
+    let signature_instruction_index = read_u16(2);
+    let public_key_offset = read_u16(4) as usize;
+    let public_key_instruction_index = read_u16(6);
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+    let message_instruction_index = read_u16(12);
+
+    // Data pulled from other instructions could be swapped without touching this one
+    if signature_instruction_index != CURRENT_INSTRUCTION
+        || public_key_instruction_index != CURRENT_INSTRUCTION
+        || message_instruction_index != CURRENT_INSTRUCTION
+    {
+        return Err(AttestationError::MalformedSignatureInstruction);
+    }
+
+    let slice = |offset: usize, len: usize| {
+        offset
+            .checked_add(len)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(AttestationError::MalformedSignatureInstruction)
+    };
+    slice(signature_offset, SIGNATURE_LEN)?;
+    let public_key = slice(public_key_offset, PUBKEY_LEN)?;
+    let message = slice(message_offset, message_size)?;
+
+    let signer = Pubkey::try_from(public_key)
+        .map_err(|_| AttestationError::MalformedSignatureInstruction)?;
+    Ok((signer, message))
+}
+
+// Signature introspection errors. Each matching program maps these onto its own error codes.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum AttestationError {
+    #[error("Instruction must follow an Ed25519 signature verification instruction")]
+    MissingSignature,
+    #[error("Ed25519 instruction must verify one signature over its own data")]
+    MalformedSignatureInstruction,
+    #[error("Signed message does not match the submitted data")]
+    MessageMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::sysvar::instructions::{
+        self, construct_instructions_data, store_current_index, BorrowedAccountMeta,
+        BorrowedInstruction,
+    };
+
+    // Ed25519 program instruction data checking one signature, with the offsets
+    // header followed by public key, signature and message
+    fn ed25519_data(signer: &Pubkey, message: &[u8]) -> Vec<u8> {
+        let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN;
+        let signature_offset = public_key_offset + PUBKEY_LEN;
+        let message_offset = signature_offset + SIGNATURE_LEN;
+        let mut data = vec![1, 0];
+        for field in [
+            signature_offset as u16,
+            CURRENT_INSTRUCTION,
+            public_key_offset as u16,
+            CURRENT_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7; SIGNATURE_LEN]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    // Instructions sysvar data for a transaction of (program, data) instructions,
+    // currently executing the one at `current`
+    fn sysvar_data(instructions: &[(Pubkey, Vec<u8>)], current: u16) -> Vec<u8> {
+        let fee_payer = Pubkey::new_unique();
+        let borrowed: Vec<BorrowedInstruction> = instructions
+            .iter()
+            .map(|(program_id, data)| BorrowedInstruction {
+                program_id,
+                accounts: vec![BorrowedAccountMeta {
+                    pubkey: &fee_payer,
+                    is_signer: true,
+                    is_writable: true,
+                }],
+                data,
+            })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        store_current_index(&mut data, current);
+        data
+    }
+
+    fn verify(
+        attestation: &ResultAttestation,
+        mut data: Vec<u8>,
+    ) -> Result<Pubkey, AttestationError> {
+        let key = instructions::ID;
+        let owner = Pubkey::default();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        attestation.verify_preceding(&account)
+    }
+
+    fn attestation() -> ResultAttestation {
+        ResultAttestation {
+            request: Pubkey::new_unique(),
+            result_hash: [3; 32],
+            runtime: 3_600,
+        }
+    }
+
+    #[test]
+    fn test_message_layout() {
+        let attestation = attestation();
+        let message = attestation.message();
+
+        assert_eq!(&message[..16], ATTESTATION_DOMAIN);
+        assert_eq!(&message[16..48], attestation.request.as_ref());
+        assert_eq!(&message[48..80], &attestation.result_hash);
+        assert_eq!(&message[80..], &3_600u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_returns_the_signing_key() {
+        let attestation = attestation();
+        let worker = Pubkey::new_unique();
+        let data = sysvar_data(
+            &[
+                (
+                    ed25519_program::ID,
+                    ed25519_data(&worker, &attestation.message()),
+                ),
+                (Pubkey::new_unique(), vec![4]),
+            ],
+            1,
+        );
+
+        assert_eq!(verify(&attestation, data), Ok(worker));
+    }
+
+    #[test]
+    fn test_wrong_signer_is_reported_to_the_caller() {
+        // The signer check against enrolled keys is the caller's, so an unknown
+        // signer must come back as itself rather than as any enrolled key
+        let attestation = attestation();
+        let enrolled = Pubkey::new_unique();
+        let impostor = Pubkey::new_unique();
+        let data = sysvar_data(
+            &[
+                (
+                    ed25519_program::ID,
+                    ed25519_data(&impostor, &attestation.message()),
+                ),
+                (Pubkey::new_unique(), vec![4]),
+            ],
+            1,
+        );
+
+        let signer = verify(&attestation, data).unwrap();
+        assert_eq!(signer, impostor);
+        assert_ne!(signer, enrolled);
+    }
+
+    #[test]
+    fn test_rejects_a_different_message() {
+        let attestation = attestation();
+        let other = ResultAttestation {
+            runtime: attestation.runtime + 1,
+            ..attestation
+        };
+        let data = sysvar_data(
+            &[
+                (
+                    ed25519_program::ID,
+                    ed25519_data(&Pubkey::new_unique(), &other.message()),
+                ),
+                (Pubkey::new_unique(), vec![4]),
+            ],
+            1,
+        );
+
+        assert_eq!(
+            verify(&attestation, data),
+            Err(AttestationError::MessageMismatch)
+        );
+    }
+
+    #[test]
+    fn test_requires_a_preceding_ed25519_instruction() {
+        let attestation = attestation();
+        let signed = ed25519_data(&Pubkey::new_unique(), &attestation.message());
+
+        // Nothing precedes the current instruction
+        let data = sysvar_data(&[(Pubkey::new_unique(), vec![4])], 0);
+        assert_eq!(
+            verify(&attestation, data),
+            Err(AttestationError::MissingSignature)
+        );
+
+        // The preceding instruction is not the Ed25519 program
+        let data = sysvar_data(
+            &[
+                (Pubkey::new_unique(), signed.clone()),
+                (Pubkey::new_unique(), vec![4]),
+            ],
+            1,
+        );
+        assert_eq!(
+            verify(&attestation, data),
+            Err(AttestationError::MissingSignature)
+        );
+
+        // The Ed25519 instruction is not immediately before the current one
+        let data = sysvar_data(
+            &[
+                (ed25519_program::ID, signed),
+                (Pubkey::new_unique(), vec![]),
+                (Pubkey::new_unique(), vec![4]),
+            ],
+            2,
+        );
+        assert_eq!(
+            verify(&attestation, data),
+            Err(AttestationError::MissingSignature)
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_signature_instructions() {
+        let attestation = attestation();
+        let signed = ed25519_data(&Pubkey::new_unique(), &attestation.message());
+        let message_size_at = SIGNATURE_OFFSETS_START + 10;
+
+        let mut two_signatures = signed.clone();
+        two_signatures[0] = 2;
+        let mut foreign_public_key = signed.clone();
+        foreign_public_key[SIGNATURE_OFFSETS_START + 6..SIGNATURE_OFFSETS_START + 8]
+            .copy_from_slice(&0u16.to_le_bytes());
+        let mut message_out_of_bounds = signed.clone();
+        message_out_of_bounds[message_size_at..message_size_at + 2]
+            .copy_from_slice(&(ATTESTATION_MESSAGE_LEN as u16 + 1).to_le_bytes());
+        let truncated = signed[..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN - 1].to_vec();
+
+        for malformed in [
+            two_signatures,
+            foreign_public_key,
+            message_out_of_bounds,
+            truncated,
+        ] {
+            let data = sysvar_data(
+                &[
+                    (ed25519_program::ID, malformed),
+                    (Pubkey::new_unique(), vec![4]),
+                ],
+                1,
+            );
+            assert_eq!(
+                verify(&attestation, data),
+                Err(AttestationError::MalformedSignatureInstruction)
+            );
+        }
+    }
+}
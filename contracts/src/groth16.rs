@@ -0,0 +1,354 @@
+// Groth16 proof verification over the bn254 curve for verifiable computations.
+// Points and scalars use the big-endian EIP-197 encoding expected by the alt_bn128
+// syscalls: G1 points are x || y (64 bytes) and G2 points are x_im || x_re || y_im || y_re
+// (128 bytes). Verification checks
+//
+//     e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+//
+// where vk_x = IC[0] + sum(input_i * IC[i + 1]) commits to the public inputs.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::alt_bn128::prelude::{
+    alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+};
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+pub const G1_LEN: usize = 64;
+pub const G2_LEN: usize = 128;
+pub const SCALAR_LEN: usize = 32;
+
+// Upper bound on public inputs, which keeps verification within the compute budget
+pub const MAX_PUBLIC_INPUTS: usize = 8;
+
+// Public inputs of a computation proof: the request it settles and its result hash
+pub const COMPUTATION_PUBLIC_INPUTS: usize = 2;
+
+// Base field modulus q, used to negate G1 points
+const BASE_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+// Scalar field modulus r. Public inputs must be canonical field elements.
+const SCALAR_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: [u8; G1_LEN],
+    pub beta_g2: [u8; G2_LEN],
+    pub gamma_g2: [u8; G2_LEN],
+    pub delta_g2: [u8; G2_LEN],
+    // One point per public input plus the constant term
+    pub ic: Vec<[u8; G1_LEN]>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Groth16Proof {
+    pub a: [u8; G1_LEN],
+    pub b: [u8; G2_LEN],
+    pub c: [u8; G1_LEN],
+}
+
+impl Groth16VerifyingKey {
+    // Hash a request registers to pin the circuit its result must be proven against
+    pub fn hash(&self) -> [u8; 32] {
+        let mut data: Vec<&[u8]> = vec![
+            &self.alpha_g1,
+            &self.beta_g2,
+            &self.gamma_g2,
+            &self.delta_g2,
+        ];
+        data.extend(self.ic.iter().map(|point| point.as_slice()));
+        keccak::hashv(&data).0
+    }
+
+    // Check a proof against this key and the given public inputs
+    pub fn verify(
+        &self,
+        proof: &Groth16Proof,
+        public_inputs: &[[u8; SCALAR_LEN]],
+    ) -> Result<(), Groth16Error> {
+        if public_inputs.len() > MAX_PUBLIC_INPUTS || public_inputs.len() + 1 != self.ic.len() {
+            return Err(Groth16Error::InvalidPublicInputCount);
+        }
+
+        // Fold the public inputs into a single G1 point
+        let mut vk_x = self.ic[0];
+        for (input, point) in public_inputs.iter().zip(self.ic[1..].iter()) {
+            if *input >= SCALAR_FIELD_MODULUS {
+                return Err(Groth16Error::PublicInputNotInField);
+            }
+            let term = alt_bn128_multiplication(&[point.as_slice(), input].concat())
+                .map_err(|_| Groth16Error::InvalidPoint)?;
+            let sum = alt_bn128_addition(&[vk_x.as_slice(), &term].concat())
+                .map_err(|_| Groth16Error::InvalidPoint)?;
+            vk_x = sum.try_into().map_err(|_| Groth16Error::InvalidPoint)?;
+        }
+
+        let pairing_input = [
+            negate_g1(&proof.a).as_slice(),
+            &proof.b,
+            &self.alpha_g1,
+            &self.beta_g2,
+            &vk_x,
+            &self.gamma_g2,
+            &proof.c,
+            &self.delta_g2,
+        ]
+        .concat();
+        let result = alt_bn128_pairing(&pairing_input).map_err(|_| Groth16Error::InvalidPoint)?;
+
+        // The syscall returns the big-endian integer 1 when the product is the identity
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        if result != one {
+            return Err(Groth16Error::ProofRejected);
+        }
+        Ok(())
+    }
+}
+
+// Public inputs a computation proof is checked against. They are derived from the
+// request rather than supplied with the proof, so a proof only settles the request and
+// result it was generated for.
+pub fn computation_public_inputs(
+    request: &Pubkey,
+    result_hash: &[u8; 32],
+) -> [[u8; SCALAR_LEN]; COMPUTATION_PUBLIC_INPUTS] {
+    [
+        hash_to_scalar(request.as_ref()),
+        hash_to_scalar(result_hash),
+    ]
+}
+
+// Keccak hash with the top three bits cleared, which always lies below the scalar modulus
+pub fn hash_to_scalar(data: &[u8]) -> [u8; SCALAR_LEN] {
+    let mut scalar = keccak::hash(data).0;
+    scalar[0] &= 0x1f;
+    scalar
+}
+
+// Negate a G1 point by replacing y with q - y. The point at infinity is its own negation.
+fn negate_g1(point: &[u8; G1_LEN]) -> [u8; G1_LEN] {
+    let mut negated = *point;
+    if point.iter().all(|&byte| byte == 0) {
+        return negated;
+    }
+
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut difference = BASE_FIELD_MODULUS[i] as i16 - point[32 + i] as i16 - borrow;
+        borrow = (difference < 0) as i16;
+        if difference < 0 {
+            difference += 256;
+        }
+        negated[32 + i] = difference as u8;
+    }
+    negated
+}
+
+// Groth16 verification errors
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Groth16Error {
+    #[error("Number of public inputs does not match the verifying key")]
+    InvalidPublicInputCount,
+    #[error("Public input is not a canonical scalar field element")]
+    PublicInputNotInField,
+    #[error("Proof or verifying key contains an invalid curve point")]
+    InvalidPoint,
+    #[error("Pairing check failed")]
+    ProofRejected,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // bn254 G2 generator in EIP-197 order: x_im || x_re || y_im || y_re
+    const G2_GENERATOR: [u8; G2_LEN] = [
+        0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb, 0x5d,
+        0x25, 0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7, 0xae, 0xf3,
+        0x12, 0xc2, 0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a, 0x00, 0x66, 0x5e,
+        0x5c, 0x44, 0x79, 0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd, 0x46, 0xde, 0xbd, 0x5c,
+        0xd9, 0x92, 0xf6, 0xed, 0x09, 0x06, 0x89, 0xd0, 0x58, 0x5f, 0xf0, 0x75, 0xec, 0x9e, 0x99,
+        0xad, 0x69, 0x0c, 0x33, 0x95, 0xbc, 0x4b, 0x31, 0x33, 0x70, 0xb3, 0x8e, 0xf3, 0x55, 0xac,
+        0xda, 0xdc, 0xd1, 0x22, 0x97, 0x5b, 0x12, 0xc8, 0x5e, 0xa5, 0xdb, 0x8c, 0x6d, 0xeb, 0x4a,
+        0xab, 0x71, 0x80, 0x8d, 0xcb, 0x40, 0x8f, 0xe3, 0xd1, 0xe7, 0x69, 0x0c, 0x43, 0xd3, 0x7b,
+        0x4c, 0xe6, 0xcc, 0x01, 0x66, 0xfa, 0x7d, 0xaa,
+    ];
+
+    fn scalar(value: u64) -> [u8; SCALAR_LEN] {
+        let mut scalar = [0u8; SCALAR_LEN];
+        scalar[24..].copy_from_slice(&value.to_be_bytes());
+        scalar
+    }
+
+    // value * G1 generator, where the generator is (1, 2)
+    fn g1(value: u64) -> [u8; G1_LEN] {
+        let mut generator = [0u8; G1_LEN];
+        generator[31] = 1;
+        generator[63] = 2;
+        alt_bn128_multiplication(&[generator.as_slice(), &scalar(value)].concat())
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    // A key and proof for one public input, built from known discrete logs with every
+    // G2 point at the generator. The pairing equation then reduces to
+    // a = alpha + (ic0 + ic1 * input) + c, here 2 + (5 + 7 * 11) + 13 = 97.
+    fn fixture() -> (Groth16VerifyingKey, Groth16Proof, [u8; SCALAR_LEN]) {
+        let key = Groth16VerifyingKey {
+            alpha_g1: g1(2),
+            beta_g2: G2_GENERATOR,
+            gamma_g2: G2_GENERATOR,
+            delta_g2: G2_GENERATOR,
+            ic: vec![g1(5), g1(7)],
+        };
+        let proof = Groth16Proof {
+            a: g1(97),
+            b: G2_GENERATOR,
+            c: g1(13),
+        };
+        (key, proof, scalar(11))
+    }
+
+    #[test]
+    fn test_accepts_valid_proof() {
+        let (key, proof, input) = fixture();
+        assert_eq!(key.verify(&proof, &[input]), Ok(()));
+    }
+
+    #[test]
+    fn test_rejects_proof_for_other_inputs() {
+        let (key, proof, _) = fixture();
+        assert_eq!(
+            key.verify(&proof, &[scalar(12)]),
+            Err(Groth16Error::ProofRejected)
+        );
+
+        let tampered = Groth16Proof { c: g1(14), ..proof };
+        assert_eq!(
+            key.verify(&tampered, &[scalar(11)]),
+            Err(Groth16Error::ProofRejected)
+        );
+    }
+
+    #[test]
+    fn test_rejects_wrong_public_input_count() {
+        let (key, proof, input) = fixture();
+        assert_eq!(
+            key.verify(&proof, &[]),
+            Err(Groth16Error::InvalidPublicInputCount)
+        );
+        assert_eq!(
+            key.verify(&proof, &[input, input]),
+            Err(Groth16Error::InvalidPublicInputCount)
+        );
+
+        let oversized = Groth16VerifyingKey {
+            ic: vec![g1(1); MAX_PUBLIC_INPUTS + 2],
+            ..key
+        };
+        let inputs = [input; MAX_PUBLIC_INPUTS + 1];
+        assert_eq!(
+            oversized.verify(&proof, &inputs),
+            Err(Groth16Error::InvalidPublicInputCount)
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_public_input() {
+        let (key, proof, _) = fixture();
+        assert_eq!(
+            key.verify(&proof, &[SCALAR_FIELD_MODULUS]),
+            Err(Groth16Error::PublicInputNotInField)
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_proof() {
+        let (key, proof, input) = fixture();
+
+        // (1, 3) is not on the curve
+        let mut off_curve = [0u8; G1_LEN];
+        off_curve[31] = 1;
+        off_curve[63] = 3;
+        let malformed = Groth16Proof {
+            a: off_curve,
+            ..proof
+        };
+        assert_eq!(
+            key.verify(&malformed, &[input]),
+            Err(Groth16Error::InvalidPoint)
+        );
+
+        let malformed = Groth16Proof {
+            b: [0xff; G2_LEN],
+            ..proof
+        };
+        assert_eq!(
+            key.verify(&malformed, &[input]),
+            Err(Groth16Error::InvalidPoint)
+        );
+
+        // A truncated encoding does not decode at all
+        let bytes = borsh::to_vec(&proof).unwrap();
+        assert!(Groth16Proof::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed_verifying_key() {
+        let (key, proof, input) = fixture();
+        let mut off_curve = [0u8; G1_LEN];
+        off_curve[31] = 1;
+        off_curve[63] = 3;
+        let malformed = Groth16VerifyingKey {
+            ic: vec![g1(5), off_curve],
+            ..key
+        };
+        assert_eq!(
+            malformed.verify(&proof, &[input]),
+            Err(Groth16Error::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn test_computation_public_inputs() {
+        let request = Pubkey::new_unique();
+        let inputs = computation_public_inputs(&request, &[0xff; 32]);
+
+        assert!(inputs.iter().all(|input| *input < SCALAR_FIELD_MODULUS));
+        assert_eq!(inputs[0], hash_to_scalar(request.as_ref()));
+        assert_eq!(inputs[1], hash_to_scalar(&[0xff; 32]));
+        assert_ne!(
+            inputs,
+            computation_public_inputs(&Pubkey::new_unique(), &[0xff; 32])
+        );
+        assert_ne!(inputs, computation_public_inputs(&request, &[0xfe; 32]));
+    }
+
+    #[test]
+    fn test_negate_g1() {
+        let point = g1(3);
+        let sum = alt_bn128_addition(&[point.as_slice(), &negate_g1(&point)].concat()).unwrap();
+        assert_eq!(sum, vec![0u8; G1_LEN]);
+        assert_eq!(negate_g1(&[0u8; G1_LEN]), [0u8; G1_LEN]);
+    }
+
+    #[test]
+    fn test_hash_pins_the_circuit() {
+        let (key, _, _) = fixture();
+        let other = Groth16VerifyingKey {
+            ic: vec![g1(5), g1(8)],
+            ..key.clone()
+        };
+        assert_eq!(key.hash(), key.clone().hash());
+        assert_ne!(key.hash(), other.hash());
+    }
+}
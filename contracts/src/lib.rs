@@ -0,0 +1,10 @@
+// Types and verification helpers shared by the Calctra on-chain programs and
+// off-chain clients. The programs themselves live under programs/, so this crate
+// never exports an entrypoint.
+
+pub mod accounts;
+pub mod attestation;
+pub mod groth16;
+pub mod offer;
+pub mod resource_vector;
+pub mod taxonomy;
@@ -0,0 +1,42 @@
+// Provider price offers signed off chain. A provider signs an offer for one of its
+// resources with its wallet key, and a requester submits the chosen offer together
+// with its request so the match is created in a single instruction. The signature is
+// checked through the same Ed25519 instruction introspection as result attestations.
+
+use solana_program::pubkey::Pubkey;
+
+use crate::resource_vector::{ResourceVector, MAX_DIMENSIONS};
+
+// Domain separator so an offer signature cannot be replayed as any other message
+pub const OFFER_DOMAIN: &[u8; 16] = b"calctra-offer-01";
+
+// Serialized offer message length: domain, resource, price, capacity, expiry, nonce
+pub const OFFER_MESSAGE_LEN: usize = 16 + 32 + 8 + 8 * MAX_DIMENSIONS + 8 + 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProviderOffer {
+    pub resource: Pubkey,
+    pub price_per_unit: u64,
+    // Largest share of the resource the offer sells to a single request
+    pub capacity: ResourceVector,
+    pub expiry: i64,
+    // Each nonce can be settled once per resource
+    pub nonce: u64,
+}
+
+impl ProviderOffer {
+    pub fn message(&self) -> [u8; OFFER_MESSAGE_LEN] {
+        let mut message = [0u8; OFFER_MESSAGE_LEN];
+        message[..16].copy_from_slice(OFFER_DOMAIN);
+        message[16..48].copy_from_slice(self.resource.as_ref());
+        message[48..56].copy_from_slice(&self.price_per_unit.to_le_bytes());
+        for (i, amount) in self.capacity.0.iter().enumerate() {
+            let at = 56 + 8 * i;
+            message[at..at + 8].copy_from_slice(&amount.to_le_bytes());
+        }
+        let tail = 56 + 8 * MAX_DIMENSIONS;
+        message[tail..tail + 8].copy_from_slice(&self.expiry.to_le_bytes());
+        message[tail + 8..].copy_from_slice(&self.nonce.to_le_bytes());
+        message
+    }
+}
@@ -0,0 +1,272 @@
+// Multi-dimensional resource vectors shared by the native and Anchor matching programs.
+// Provider offers and request requirements are both expressed as vectors indexed by a
+// registered dimension ID, and matching compares them dimension by dimension.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+
+// Number of dimension slots in a vector. Unregistered slots are reserved and must be zero.
+pub const MAX_DIMENSIONS: usize = 16;
+
+// Units a dimension can be measured in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Count,
+    Gibibytes,
+    GigabitsPerSecond,
+    GigabytesPerSecond,
+    Gflops,
+    Score,
+}
+
+// Registered resource dimensions. The discriminant is the index into a ResourceVector.
+//
+// Benchmark dimensions (FP64 throughput, memory bandwidth and GPU tensor score) are
+// performance thresholds backed by verifier attestations rather than self-reported
+// figures, and are never reserved by a match.
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    ComputationPower = 0,
+    CpuCores = 1,
+    MemoryGb = 2,
+    StorageGb = 3,
+    GpuCount = 4,
+    GpuMemoryGb = 5,
+    FpgaCount = 6,
+    InterconnectGbps = 7,
+    LocalNvmeGb = 8,
+    MemoryBandwidth = 9,
+    TensorScore = 10,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 11] = [
+        Self::ComputationPower,
+        Self::CpuCores,
+        Self::MemoryGb,
+        Self::StorageGb,
+        Self::GpuCount,
+        Self::GpuMemoryGb,
+        Self::FpgaCount,
+        Self::InterconnectGbps,
+        Self::LocalNvmeGb,
+        Self::MemoryBandwidth,
+        Self::TensorScore,
+    ];
+
+    pub const BENCHMARKS: [Dimension; 3] = [
+        Self::ComputationPower,
+        Self::MemoryBandwidth,
+        Self::TensorScore,
+    ];
+
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    pub fn is_benchmark(self) -> bool {
+        Self::BENCHMARKS.contains(&self)
+    }
+
+    pub fn unit(self) -> Unit {
+        match self {
+            Self::ComputationPower => Unit::Gflops,
+            Self::MemoryBandwidth => Unit::GigabytesPerSecond,
+            Self::TensorScore => Unit::Score,
+            Self::CpuCores | Self::GpuCount | Self::FpgaCount => Unit::Count,
+            Self::MemoryGb | Self::StorageGb | Self::GpuMemoryGb | Self::LocalNvmeGb => {
+                Unit::Gibibytes
+            }
+            Self::InterconnectGbps => Unit::GigabitsPerSecond,
+        }
+    }
+}
+
+impl TryFrom<u8> for Dimension {
+    type Error = ProgramError;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(id as usize)
+            .copied()
+            .ok_or(ProgramError::InvalidArgument)
+    }
+}
+
+// Quantities per dimension, encoded on chain as a fixed array of u64 indexed by dimension ID
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceVector(pub [u64; MAX_DIMENSIONS]);
+
+impl ResourceVector {
+    // Borsh-encoded length
+    pub const LEN: usize = 8 * MAX_DIMENSIONS;
+
+    pub fn get(&self, dimension: Dimension) -> u64 {
+        self.0[dimension.id() as usize]
+    }
+
+    pub fn set(&mut self, dimension: Dimension, amount: u64) {
+        self.0[dimension.id() as usize] = amount;
+    }
+
+    // Reject vectors that use reserved, unregistered dimension slots
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if self.0[Dimension::ALL.len()..]
+            .iter()
+            .any(|&amount| amount != 0)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    // Dimensions a match reserves, with benchmark thresholds zeroed out
+    pub fn consumable(&self) -> ResourceVector {
+        let mut result = *self;
+        for dimension in Dimension::BENCHMARKS {
+            result.set(dimension, 0);
+        }
+        result
+    }
+
+    // Only the benchmark threshold dimensions
+    pub fn benchmarks(&self) -> ResourceVector {
+        let mut result = ResourceVector::default();
+        for dimension in Dimension::BENCHMARKS {
+            result.set(dimension, self.get(dimension));
+        }
+        result
+    }
+
+    // Add a reservation, failing on overflow
+    pub fn checked_add(&self, other: &ResourceVector) -> Option<ResourceVector> {
+        let mut result = *self;
+        for (amount, other) in result.0.iter_mut().zip(other.0.iter()) {
+            *amount = amount.checked_add(*other)?;
+        }
+        Some(result)
+    }
+
+    // Release a reservation, failing if any dimension would go negative
+    pub fn checked_sub(&self, other: &ResourceVector) -> Option<ResourceVector> {
+        let mut result = *self;
+        for (amount, other) in result.0.iter_mut().zip(other.0.iter()) {
+            *amount = amount.checked_sub(*other)?;
+        }
+        Some(result)
+    }
+
+    // Compare an offer against requirements dimension by dimension, returning the
+    // first dimension the offer falls short on
+    pub fn covers(&self, requirements: &ResourceVector) -> Result<(), Dimension> {
+        for dimension in Dimension::ALL {
+            if self.get(dimension) < requirements.get(dimension) {
+                return Err(dimension);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(amounts: &[(Dimension, u64)]) -> ResourceVector {
+        let mut vector = ResourceVector::default();
+        for &(dimension, amount) in amounts {
+            vector.set(dimension, amount);
+        }
+        vector
+    }
+
+    #[test]
+    fn test_validate_rejects_reserved_dimensions() {
+        assert!(vector(&[(Dimension::TensorScore, 5)]).validate().is_ok());
+
+        let mut reserved = ResourceVector::default();
+        reserved.0[Dimension::ALL.len()] = 1;
+        assert_eq!(reserved.validate(), Err(ProgramError::InvalidArgument));
+
+        let mut last = ResourceVector::default();
+        last.0[MAX_DIMENSIONS - 1] = 1;
+        assert_eq!(last.validate(), Err(ProgramError::InvalidArgument));
+    }
+
+    #[test]
+    fn test_dimension_ids_round_trip() {
+        for dimension in Dimension::ALL {
+            assert_eq!(Dimension::try_from(dimension.id()), Ok(dimension));
+        }
+        assert!(Dimension::try_from(Dimension::ALL.len() as u8).is_err());
+    }
+
+    #[test]
+    fn test_covers_reports_first_short_dimension() {
+        let offer = vector(&[(Dimension::CpuCores, 32), (Dimension::MemoryGb, 64)]);
+
+        assert_eq!(offer.covers(&vector(&[(Dimension::CpuCores, 32)])), Ok(()));
+        assert_eq!(
+            offer.covers(&vector(&[
+                (Dimension::MemoryGb, 65),
+                (Dimension::GpuCount, 1)
+            ])),
+            Err(Dimension::MemoryGb)
+        );
+        assert_eq!(
+            offer.covers(&vector(&[(Dimension::GpuCount, 1)])),
+            Err(Dimension::GpuCount)
+        );
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let allocated = vector(&[(Dimension::CpuCores, 8)]);
+
+        assert_eq!(
+            allocated.checked_add(&allocated),
+            Some(vector(&[(Dimension::CpuCores, 16)]))
+        );
+        assert_eq!(
+            allocated.checked_sub(&vector(&[(Dimension::CpuCores, 3)])),
+            Some(vector(&[(Dimension::CpuCores, 5)]))
+        );
+        assert_eq!(
+            allocated.checked_sub(&vector(&[(Dimension::CpuCores, 9)])),
+            None
+        );
+        assert_eq!(
+            allocated.checked_sub(&vector(&[(Dimension::MemoryGb, 1)])),
+            None
+        );
+        assert_eq!(
+            vector(&[(Dimension::StorageGb, u64::MAX)])
+                .checked_add(&vector(&[(Dimension::StorageGb, 1)])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_benchmarks_are_never_reserved() {
+        let requirements = vector(&[
+            (Dimension::CpuCores, 4),
+            (Dimension::ComputationPower, 1_000),
+            (Dimension::MemoryBandwidth, 200),
+            (Dimension::TensorScore, 50),
+        ]);
+
+        assert_eq!(
+            requirements.consumable(),
+            vector(&[(Dimension::CpuCores, 4)])
+        );
+        assert_eq!(
+            requirements.benchmarks(),
+            vector(&[
+                (Dimension::ComputationPower, 1_000),
+                (Dimension::MemoryBandwidth, 200),
+                (Dimension::TensorScore, 50),
+            ])
+        );
+    }
+}
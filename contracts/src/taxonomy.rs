@@ -0,0 +1,788 @@
+// Canonical resource taxonomy shared by the native and Anchor matching programs.
+// Every value has a fixed on-chain encoding so that matching can compare
+// requirements and offers exactly and clients cannot misspell them.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+
+// Accelerator models, encoded on chain as a u16. Zero means no accelerator.
+#[repr(u16)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AcceleratorModel {
+    #[default]
+    None = 0,
+    // NVIDIA GPUs
+    NvidiaV100 = 1,
+    NvidiaA100 = 2,
+    NvidiaH100 = 3,
+    NvidiaL4 = 4,
+    NvidiaL40S = 5,
+    NvidiaRtx4090 = 6,
+    // AMD GPUs
+    AmdMi250 = 100,
+    AmdMi300X = 101,
+    // Intel GPUs
+    IntelMax1550 = 200,
+    // Tensor processors
+    GoogleTpuV4 = 300,
+    GoogleTpuV5e = 301,
+    // FPGAs
+    XilinxAlveoU250 = 400,
+    IntelAgilex7 = 401,
+}
+
+impl AcceleratorModel {
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+}
+
+impl TryFrom<u16> for AcceleratorModel {
+    type Error = ProgramError;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        Ok(match code {
+            0 => Self::None,
+            1 => Self::NvidiaV100,
+            2 => Self::NvidiaA100,
+            3 => Self::NvidiaH100,
+            4 => Self::NvidiaL4,
+            5 => Self::NvidiaL40S,
+            6 => Self::NvidiaRtx4090,
+            100 => Self::AmdMi250,
+            101 => Self::AmdMi300X,
+            200 => Self::IntelMax1550,
+            300 => Self::GoogleTpuV4,
+            301 => Self::GoogleTpuV5e,
+            400 => Self::XilinxAlveoU250,
+            401 => Self::IntelAgilex7,
+            _ => return Err(ProgramError::InvalidArgument),
+        })
+    }
+}
+
+// CPU instruction set architectures, encoded on chain as a u8
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CpuArchitecture {
+    #[default]
+    X86_64 = 0,
+    Aarch64 = 1,
+    Riscv64 = 2,
+    Ppc64le = 3,
+}
+
+impl CpuArchitecture {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for CpuArchitecture {
+    type Error = ProgramError;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Ok(match code {
+            0 => Self::X86_64,
+            1 => Self::Aarch64,
+            2 => Self::Riscv64,
+            3 => Self::Ppc64le,
+            _ => return Err(ProgramError::InvalidArgument),
+        })
+    }
+}
+
+// Scientific job categories, encoded on chain as a u8
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JobCategory {
+    #[default]
+    General = 0,
+    MolecularDynamics = 1,
+    QuantumChemistry = 2,
+    Genomics = 3,
+    ClimateModeling = 4,
+    FluidDynamics = 5,
+    MachineLearningTraining = 6,
+    MachineLearningInference = 7,
+    DataAnalytics = 8,
+    Rendering = 9,
+}
+
+impl JobCategory {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for JobCategory {
+    type Error = ProgramError;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Ok(match code {
+            0 => Self::General,
+            1 => Self::MolecularDynamics,
+            2 => Self::QuantumChemistry,
+            3 => Self::Genomics,
+            4 => Self::ClimateModeling,
+            5 => Self::FluidDynamics,
+            6 => Self::MachineLearningTraining,
+            7 => Self::MachineLearningInference,
+            8 => Self::DataAnalytics,
+            9 => Self::Rendering,
+            _ => return Err(ProgramError::InvalidArgument),
+        })
+    }
+}
+
+// Trusted execution environment platforms, encoded on chain as a u8
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TeePlatform {
+    IntelSgx = 1,
+    IntelTdx = 2,
+    AmdSevSnp = 3,
+    AwsNitro = 4,
+    ArmCca = 5,
+}
+
+// Enclave measurements are stored in a fixed 48-byte slot. Platforms with a
+// 32-byte measurement (SGX MRENCLAVE) are zero-padded on the right.
+pub const TEE_MEASUREMENT_LEN: usize = 48;
+
+impl TeePlatform {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    // Length of the platform's native measurement within the 48-byte slot
+    pub fn measurement_len(self) -> usize {
+        match self {
+            Self::IntelSgx => 32,
+            Self::IntelTdx | Self::AmdSevSnp | Self::AwsNitro | Self::ArmCca => 48,
+        }
+    }
+}
+
+impl TryFrom<u8> for TeePlatform {
+    type Error = ProgramError;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Ok(match code {
+            1 => Self::IntelSgx,
+            2 => Self::IntelTdx,
+            3 => Self::AmdSevSnp,
+            4 => Self::AwsNitro,
+            5 => Self::ArmCca,
+            _ => return Err(ProgramError::InvalidArgument),
+        })
+    }
+}
+
+// Software environment capabilities a provider can publish. The discriminant is
+// the bit index in a CapabilitySet.
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Capability {
+    // GPU compute stacks
+    Cuda11 = 0,
+    Cuda12 = 1,
+    Rocm = 2,
+    OpenCl = 3,
+    OneApi = 4,
+    Nccl = 5,
+    // Parallel runtimes
+    Mpi = 16,
+    OpenMp = 17,
+    Slurm = 18,
+    // Container runtimes
+    Docker = 32,
+    Podman = 33,
+    Singularity = 34,
+    Kubernetes = 35,
+    // Language runtimes and frameworks
+    Python3 = 48,
+    Julia = 49,
+    R = 50,
+    PyTorch = 51,
+    TensorFlow = 52,
+    Jax = 53,
+    // Scientific packages
+    Gromacs = 64,
+    Lammps = 65,
+    Amber = 66,
+    OpenFoam = 67,
+    // Hardware features
+    InfinibandRdma = 96,
+    NvLink = 97,
+    EccMemory = 98,
+}
+
+impl Capability {
+    pub fn bit(self) -> u8 {
+        self as u8
+    }
+}
+
+// Compact set of capabilities, encoded on chain as a u128 bitset
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CapabilitySet(pub u128);
+
+impl CapabilitySet {
+    pub fn from_capabilities(capabilities: &[Capability]) -> Self {
+        let mut set = Self::default();
+        for capability in capabilities {
+            set.insert(*capability);
+        }
+        set
+    }
+
+    pub fn insert(&mut self, capability: Capability) {
+        self.0 |= 1 << capability.bit();
+    }
+
+    pub fn contains(&self, capability: Capability) -> bool {
+        self.0 & (1 << capability.bit()) != 0
+    }
+
+    // Capabilities in `required` that this set lacks
+    pub fn missing(&self, required: CapabilitySet) -> CapabilitySet {
+        CapabilitySet(required.0 & !self.0)
+    }
+
+    // Number of capabilities in `optional` that this set provides
+    pub fn matched_count(&self, optional: CapabilitySet) -> u32 {
+        (optional.0 & self.0).count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+// Continents, the top level of the region hierarchy, encoded on chain as a u8
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Continent {
+    Africa = 0,
+    Antarctica = 1,
+    Asia = 2,
+    Europe = 3,
+    NorthAmerica = 4,
+    Oceania = 5,
+    SouthAmerica = 6,
+}
+
+impl Continent {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for Continent {
+    type Error = ProgramError;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Ok(match code {
+            0 => Self::Africa,
+            1 => Self::Antarctica,
+            2 => Self::Asia,
+            3 => Self::Europe,
+            4 => Self::NorthAmerica,
+            5 => Self::Oceania,
+            6 => Self::SouthAmerica,
+            _ => return Err(ProgramError::InvalidArgument),
+        })
+    }
+}
+
+// ISO-3166-1 alpha-2 country code, encoded on chain as two uppercase ASCII bytes
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegionCode(pub [u8; 2]);
+
+impl RegionCode {
+    pub fn code(self) -> [u8; 2] {
+        self.0
+    }
+
+    pub fn continent(self) -> Continent {
+        // Every constructed RegionCode is in the table, see TryFrom below
+        let index = ISO_3166_ALPHA2
+            .binary_search_by_key(&self.0, |(code, _)| *code)
+            .unwrap_or(0);
+        ISO_3166_ALPHA2[index].1
+    }
+
+    pub fn is_in_european_union(self) -> bool {
+        EUROPEAN_UNION.contains(&self.0)
+    }
+
+    pub fn is_in_european_economic_area(self) -> bool {
+        self.is_in_european_union() || EEA_EFTA_MEMBERS.contains(&self.0)
+    }
+}
+
+impl TryFrom<[u8; 2]> for RegionCode {
+    type Error = ProgramError;
+
+    fn try_from(code: [u8; 2]) -> Result<Self, Self::Error> {
+        ISO_3166_ALPHA2
+            .binary_search_by_key(&code, |(code, _)| *code)
+            .map(|_| RegionCode(code))
+            .map_err(|_| ProgramError::InvalidArgument)
+    }
+}
+
+// A node in the region hierarchy: continent, regulatory bloc, country or a
+// provider-defined zone within a country. Used both for hard data residency
+// constraints and for soft location preferences.
+//
+// On-chain encoding is four bytes: a kind tag followed by its payload.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionScope {
+    Continent(Continent),
+    EuropeanUnion,
+    EuropeanEconomicArea,
+    Country(RegionCode),
+    Zone(RegionCode, u8),
+}
+
+impl RegionScope {
+    // Whether a resource located in `region`/`zone` lies inside this scope
+    pub fn contains(&self, region: RegionCode, zone: u8) -> bool {
+        match *self {
+            Self::Continent(continent) => region.continent() == continent,
+            Self::EuropeanUnion => region.is_in_european_union(),
+            Self::EuropeanEconomicArea => region.is_in_european_economic_area(),
+            Self::Country(country) => region == country,
+            Self::Zone(country, scope_zone) => region == country && zone == scope_zone,
+        }
+    }
+
+    pub fn code(self) -> [u8; 4] {
+        match self {
+            Self::Continent(continent) => [0, continent.code(), 0, 0],
+            Self::EuropeanUnion => [1, 0, 0, 0],
+            Self::EuropeanEconomicArea => [2, 0, 0, 0],
+            Self::Country(RegionCode([a, b])) => [3, a, b, 0],
+            Self::Zone(RegionCode([a, b]), zone) => [4, a, b, zone],
+        }
+    }
+}
+
+impl TryFrom<[u8; 4]> for RegionScope {
+    type Error = ProgramError;
+
+    fn try_from(code: [u8; 4]) -> Result<Self, Self::Error> {
+        Ok(match code {
+            [0, continent, 0, 0] => Self::Continent(Continent::try_from(continent)?),
+            [1, 0, 0, 0] => Self::EuropeanUnion,
+            [2, 0, 0, 0] => Self::EuropeanEconomicArea,
+            [3, a, b, 0] => Self::Country(RegionCode::try_from([a, b])?),
+            [4, a, b, zone] => Self::Zone(RegionCode::try_from([a, b])?, zone),
+            _ => return Err(ProgramError::InvalidArgument),
+        })
+    }
+}
+
+// How closely a resource location matches a preferred location, from the
+// most specific level of the hierarchy down
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Locality {
+    Remote = 0,
+    SameContinent = 1,
+    SameCountry = 2,
+    SameZone = 3,
+}
+
+impl Locality {
+    pub fn between(
+        preferred: RegionCode,
+        preferred_zone: u8,
+        region: RegionCode,
+        zone: u8,
+    ) -> Self {
+        if region == preferred && zone == preferred_zone {
+            Self::SameZone
+        } else if region == preferred {
+            Self::SameCountry
+        } else if region.continent() == preferred.continent() {
+            Self::SameContinent
+        } else {
+            Self::Remote
+        }
+    }
+}
+
+// European Union member states
+pub const EUROPEAN_UNION: [[u8; 2]; 27] = [
+    *b"AT", *b"BE", *b"BG", *b"CY", *b"CZ", *b"DE", *b"DK", *b"EE", *b"ES", *b"FI", *b"FR", *b"GR",
+    *b"HR", *b"HU", *b"IE", *b"IT", *b"LT", *b"LU", *b"LV", *b"MT", *b"NL", *b"PL", *b"PT", *b"RO",
+    *b"SE", *b"SI", *b"SK",
+];
+
+// EFTA states that are part of the European Economic Area
+pub const EEA_EFTA_MEMBERS: [[u8; 2]; 3] = [*b"IS", *b"LI", *b"NO"];
+
+// Officially assigned ISO-3166-1 alpha-2 codes and their continents, sorted for binary search
+pub const ISO_3166_ALPHA2: [([u8; 2], Continent); 249] = [
+    (*b"AD", Continent::Europe),
+    (*b"AE", Continent::Asia),
+    (*b"AF", Continent::Asia),
+    (*b"AG", Continent::NorthAmerica),
+    (*b"AI", Continent::NorthAmerica),
+    (*b"AL", Continent::Europe),
+    (*b"AM", Continent::Asia),
+    (*b"AO", Continent::Africa),
+    (*b"AQ", Continent::Antarctica),
+    (*b"AR", Continent::SouthAmerica),
+    (*b"AS", Continent::Oceania),
+    (*b"AT", Continent::Europe),
+    (*b"AU", Continent::Oceania),
+    (*b"AW", Continent::NorthAmerica),
+    (*b"AX", Continent::Europe),
+    (*b"AZ", Continent::Asia),
+    (*b"BA", Continent::Europe),
+    (*b"BB", Continent::NorthAmerica),
+    (*b"BD", Continent::Asia),
+    (*b"BE", Continent::Europe),
+    (*b"BF", Continent::Africa),
+    (*b"BG", Continent::Europe),
+    (*b"BH", Continent::Asia),
+    (*b"BI", Continent::Africa),
+    (*b"BJ", Continent::Africa),
+    (*b"BL", Continent::NorthAmerica),
+    (*b"BM", Continent::NorthAmerica),
+    (*b"BN", Continent::Asia),
+    (*b"BO", Continent::SouthAmerica),
+    (*b"BQ", Continent::NorthAmerica),
+    (*b"BR", Continent::SouthAmerica),
+    (*b"BS", Continent::NorthAmerica),
+    (*b"BT", Continent::Asia),
+    (*b"BV", Continent::Antarctica),
+    (*b"BW", Continent::Africa),
+    (*b"BY", Continent::Europe),
+    (*b"BZ", Continent::NorthAmerica),
+    (*b"CA", Continent::NorthAmerica),
+    (*b"CC", Continent::Oceania),
+    (*b"CD", Continent::Africa),
+    (*b"CF", Continent::Africa),
+    (*b"CG", Continent::Africa),
+    (*b"CH", Continent::Europe),
+    (*b"CI", Continent::Africa),
+    (*b"CK", Continent::Oceania),
+    (*b"CL", Continent::SouthAmerica),
+    (*b"CM", Continent::Africa),
+    (*b"CN", Continent::Asia),
+    (*b"CO", Continent::SouthAmerica),
+    (*b"CR", Continent::NorthAmerica),
+    (*b"CU", Continent::NorthAmerica),
+    (*b"CV", Continent::Africa),
+    (*b"CW", Continent::NorthAmerica),
+    (*b"CX", Continent::Oceania),
+    (*b"CY", Continent::Asia),
+    (*b"CZ", Continent::Europe),
+    (*b"DE", Continent::Europe),
+    (*b"DJ", Continent::Africa),
+    (*b"DK", Continent::Europe),
+    (*b"DM", Continent::NorthAmerica),
+    (*b"DO", Continent::NorthAmerica),
+    (*b"DZ", Continent::Africa),
+    (*b"EC", Continent::SouthAmerica),
+    (*b"EE", Continent::Europe),
+    (*b"EG", Continent::Africa),
+    (*b"EH", Continent::Africa),
+    (*b"ER", Continent::Africa),
+    (*b"ES", Continent::Europe),
+    (*b"ET", Continent::Africa),
+    (*b"FI", Continent::Europe),
+    (*b"FJ", Continent::Oceania),
+    (*b"FK", Continent::SouthAmerica),
+    (*b"FM", Continent::Oceania),
+    (*b"FO", Continent::Europe),
+    (*b"FR", Continent::Europe),
+    (*b"GA", Continent::Africa),
+    (*b"GB", Continent::Europe),
+    (*b"GD", Continent::NorthAmerica),
+    (*b"GE", Continent::Asia),
+    (*b"GF", Continent::SouthAmerica),
+    (*b"GG", Continent::Europe),
+    (*b"GH", Continent::Africa),
+    (*b"GI", Continent::Europe),
+    (*b"GL", Continent::NorthAmerica),
+    (*b"GM", Continent::Africa),
+    (*b"GN", Continent::Africa),
+    (*b"GP", Continent::NorthAmerica),
+    (*b"GQ", Continent::Africa),
+    (*b"GR", Continent::Europe),
+    (*b"GS", Continent::SouthAmerica),
+    (*b"GT", Continent::NorthAmerica),
+    (*b"GU", Continent::Oceania),
+    (*b"GW", Continent::Africa),
+    (*b"GY", Continent::SouthAmerica),
+    (*b"HK", Continent::Asia),
+    (*b"HM", Continent::Antarctica),
+    (*b"HN", Continent::NorthAmerica),
+    (*b"HR", Continent::Europe),
+    (*b"HT", Continent::NorthAmerica),
+    (*b"HU", Continent::Europe),
+    (*b"ID", Continent::Asia),
+    (*b"IE", Continent::Europe),
+    (*b"IL", Continent::Asia),
+    (*b"IM", Continent::Europe),
+    (*b"IN", Continent::Asia),
+    (*b"IO", Continent::Asia),
+    (*b"IQ", Continent::Asia),
+    (*b"IR", Continent::Asia),
+    (*b"IS", Continent::Europe),
+    (*b"IT", Continent::Europe),
+    (*b"JE", Continent::Europe),
+    (*b"JM", Continent::NorthAmerica),
+    (*b"JO", Continent::Asia),
+    (*b"JP", Continent::Asia),
+    (*b"KE", Continent::Africa),
+    (*b"KG", Continent::Asia),
+    (*b"KH", Continent::Asia),
+    (*b"KI", Continent::Oceania),
+    (*b"KM", Continent::Africa),
+    (*b"KN", Continent::NorthAmerica),
+    (*b"KP", Continent::Asia),
+    (*b"KR", Continent::Asia),
+    (*b"KW", Continent::Asia),
+    (*b"KY", Continent::NorthAmerica),
+    (*b"KZ", Continent::Asia),
+    (*b"LA", Continent::Asia),
+    (*b"LB", Continent::Asia),
+    (*b"LC", Continent::NorthAmerica),
+    (*b"LI", Continent::Europe),
+    (*b"LK", Continent::Asia),
+    (*b"LR", Continent::Africa),
+    (*b"LS", Continent::Africa),
+    (*b"LT", Continent::Europe),
+    (*b"LU", Continent::Europe),
+    (*b"LV", Continent::Europe),
+    (*b"LY", Continent::Africa),
+    (*b"MA", Continent::Africa),
+    (*b"MC", Continent::Europe),
+    (*b"MD", Continent::Europe),
+    (*b"ME", Continent::Europe),
+    (*b"MF", Continent::NorthAmerica),
+    (*b"MG", Continent::Africa),
+    (*b"MH", Continent::Oceania),
+    (*b"MK", Continent::Europe),
+    (*b"ML", Continent::Africa),
+    (*b"MM", Continent::Asia),
+    (*b"MN", Continent::Asia),
+    (*b"MO", Continent::Asia),
+    (*b"MP", Continent::Oceania),
+    (*b"MQ", Continent::NorthAmerica),
+    (*b"MR", Continent::Africa),
+    (*b"MS", Continent::NorthAmerica),
+    (*b"MT", Continent::Europe),
+    (*b"MU", Continent::Africa),
+    (*b"MV", Continent::Asia),
+    (*b"MW", Continent::Africa),
+    (*b"MX", Continent::NorthAmerica),
+    (*b"MY", Continent::Asia),
+    (*b"MZ", Continent::Africa),
+    (*b"NA", Continent::Africa),
+    (*b"NC", Continent::Oceania),
+    (*b"NE", Continent::Africa),
+    (*b"NF", Continent::Oceania),
+    (*b"NG", Continent::Africa),
+    (*b"NI", Continent::NorthAmerica),
+    (*b"NL", Continent::Europe),
+    (*b"NO", Continent::Europe),
+    (*b"NP", Continent::Asia),
+    (*b"NR", Continent::Oceania),
+    (*b"NU", Continent::Oceania),
+    (*b"NZ", Continent::Oceania),
+    (*b"OM", Continent::Asia),
+    (*b"PA", Continent::NorthAmerica),
+    (*b"PE", Continent::SouthAmerica),
+    (*b"PF", Continent::Oceania),
+    (*b"PG", Continent::Oceania),
+    (*b"PH", Continent::Asia),
+    (*b"PK", Continent::Asia),
+    (*b"PL", Continent::Europe),
+    (*b"PM", Continent::NorthAmerica),
+    (*b"PN", Continent::Oceania),
+    (*b"PR", Continent::NorthAmerica),
+    (*b"PS", Continent::Asia),
+    (*b"PT", Continent::Europe),
+    (*b"PW", Continent::Oceania),
+    (*b"PY", Continent::SouthAmerica),
+    (*b"QA", Continent::Asia),
+    (*b"RE", Continent::Africa),
+    (*b"RO", Continent::Europe),
+    (*b"RS", Continent::Europe),
+    (*b"RU", Continent::Europe),
+    (*b"RW", Continent::Africa),
+    (*b"SA", Continent::Asia),
+    (*b"SB", Continent::Oceania),
+    (*b"SC", Continent::Africa),
+    (*b"SD", Continent::Africa),
+    (*b"SE", Continent::Europe),
+    (*b"SG", Continent::Asia),
+    (*b"SH", Continent::Africa),
+    (*b"SI", Continent::Europe),
+    (*b"SJ", Continent::Europe),
+    (*b"SK", Continent::Europe),
+    (*b"SL", Continent::Africa),
+    (*b"SM", Continent::Europe),
+    (*b"SN", Continent::Africa),
+    (*b"SO", Continent::Africa),
+    (*b"SR", Continent::SouthAmerica),
+    (*b"SS", Continent::Africa),
+    (*b"ST", Continent::Africa),
+    (*b"SV", Continent::NorthAmerica),
+    (*b"SX", Continent::NorthAmerica),
+    (*b"SY", Continent::Asia),
+    (*b"SZ", Continent::Africa),
+    (*b"TC", Continent::NorthAmerica),
+    (*b"TD", Continent::Africa),
+    (*b"TF", Continent::Antarctica),
+    (*b"TG", Continent::Africa),
+    (*b"TH", Continent::Asia),
+    (*b"TJ", Continent::Asia),
+    (*b"TK", Continent::Oceania),
+    (*b"TL", Continent::Asia),
+    (*b"TM", Continent::Asia),
+    (*b"TN", Continent::Africa),
+    (*b"TO", Continent::Oceania),
+    (*b"TR", Continent::Europe),
+    (*b"TT", Continent::NorthAmerica),
+    (*b"TV", Continent::Oceania),
+    (*b"TW", Continent::Asia),
+    (*b"TZ", Continent::Africa),
+    (*b"UA", Continent::Europe),
+    (*b"UG", Continent::Africa),
+    (*b"UM", Continent::Oceania),
+    (*b"US", Continent::NorthAmerica),
+    (*b"UY", Continent::SouthAmerica),
+    (*b"UZ", Continent::Asia),
+    (*b"VA", Continent::Europe),
+    (*b"VC", Continent::NorthAmerica),
+    (*b"VE", Continent::SouthAmerica),
+    (*b"VG", Continent::NorthAmerica),
+    (*b"VI", Continent::NorthAmerica),
+    (*b"VN", Continent::Asia),
+    (*b"VU", Continent::Oceania),
+    (*b"WF", Continent::Oceania),
+    (*b"WS", Continent::Oceania),
+    (*b"YE", Continent::Asia),
+    (*b"YT", Continent::Africa),
+    (*b"ZA", Continent::Africa),
+    (*b"ZM", Continent::Africa),
+    (*b"ZW", Continent::Africa),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(code: &[u8; 2]) -> RegionCode {
+        RegionCode::try_from(*code).unwrap()
+    }
+
+    #[test]
+    fn test_region_codes_are_validated() {
+        assert!(ISO_3166_ALPHA2.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(region(b"DE").continent(), Continent::Europe);
+        assert_eq!(region(b"JP").continent(), Continent::Asia);
+        assert!(RegionCode::try_from(*b"XX").is_err());
+        assert!(RegionCode::try_from(*b"de").is_err());
+    }
+
+    #[test]
+    fn test_region_scope_matching() {
+        let berlin = (region(b"DE"), 1);
+
+        assert!(RegionScope::Continent(Continent::Europe).contains(berlin.0, berlin.1));
+        assert!(!RegionScope::Continent(Continent::Asia).contains(berlin.0, berlin.1));
+        assert!(RegionScope::EuropeanUnion.contains(berlin.0, berlin.1));
+        assert!(!RegionScope::EuropeanUnion.contains(region(b"NO"), 0));
+        assert!(RegionScope::EuropeanEconomicArea.contains(region(b"NO"), 0));
+        assert!(!RegionScope::EuropeanEconomicArea.contains(region(b"CH"), 0));
+        assert!(RegionScope::Country(region(b"DE")).contains(berlin.0, berlin.1));
+        assert!(!RegionScope::Country(region(b"FR")).contains(berlin.0, berlin.1));
+        assert!(RegionScope::Zone(region(b"DE"), 1).contains(berlin.0, berlin.1));
+        assert!(!RegionScope::Zone(region(b"DE"), 2).contains(berlin.0, berlin.1));
+    }
+
+    #[test]
+    fn test_region_scope_codes_round_trip() {
+        let scopes = [
+            RegionScope::Continent(Continent::Oceania),
+            RegionScope::EuropeanUnion,
+            RegionScope::EuropeanEconomicArea,
+            RegionScope::Country(region(b"CA")),
+            RegionScope::Zone(region(b"US"), 7),
+        ];
+        for scope in scopes {
+            assert_eq!(RegionScope::try_from(scope.code()), Ok(scope));
+        }
+
+        // Unknown kinds, stray payload bytes and invalid countries are rejected
+        assert!(RegionScope::try_from([5, 0, 0, 0]).is_err());
+        assert!(RegionScope::try_from([1, 0, 0, 1]).is_err());
+        assert!(RegionScope::try_from([0, 7, 0, 0]).is_err());
+        assert!(RegionScope::try_from([3, b'X', b'X', 0]).is_err());
+    }
+
+    #[test]
+    fn test_locality_ranks_the_region_hierarchy() {
+        let preferred = region(b"DE");
+
+        assert_eq!(
+            Locality::between(preferred, 1, region(b"DE"), 1),
+            Locality::SameZone
+        );
+        assert_eq!(
+            Locality::between(preferred, 1, region(b"DE"), 2),
+            Locality::SameCountry
+        );
+        assert_eq!(
+            Locality::between(preferred, 1, region(b"FR"), 1),
+            Locality::SameContinent
+        );
+        assert_eq!(
+            Locality::between(preferred, 1, region(b"US"), 1),
+            Locality::Remote
+        );
+        assert!(Locality::SameZone > Locality::SameCountry);
+    }
+
+    #[test]
+    fn test_capability_sets() {
+        let offered = CapabilitySet::from_capabilities(&[Capability::Cuda12, Capability::Mpi]);
+        let required = CapabilitySet::from_capabilities(&[Capability::Cuda12, Capability::Docker]);
+
+        assert!(offered.contains(Capability::Mpi));
+        assert!(!offered.contains(Capability::Docker));
+        assert_eq!(
+            offered.missing(required),
+            CapabilitySet::from_capabilities(&[Capability::Docker])
+        );
+        assert!(offered.missing(CapabilitySet::default()).is_empty());
+        assert_eq!(offered.matched_count(required), 1);
+    }
+
+    #[test]
+    fn test_taxonomy_codes_round_trip() {
+        let models = [
+            AcceleratorModel::None,
+            AcceleratorModel::NvidiaH100,
+            AcceleratorModel::IntelAgilex7,
+        ];
+        for model in models {
+            assert_eq!(AcceleratorModel::try_from(model.code()), Ok(model));
+        }
+        assert!(AcceleratorModel::try_from(7).is_err());
+        assert_eq!(CpuArchitecture::try_from(3), Ok(CpuArchitecture::Ppc64le));
+        assert!(CpuArchitecture::try_from(4).is_err());
+        assert_eq!(JobCategory::try_from(9), Ok(JobCategory::Rendering));
+        assert!(JobCategory::try_from(10).is_err());
+        assert!(TeePlatform::try_from(0).is_err());
+    }
+}
@@ -1,106 +0,0 @@
-use solana_program::{
-    account_info::{next_account_info, AccountInfo},
-    entrypoint,
-    entrypoint::ProgramResult,
-    msg,
-    program_error::ProgramError,
-    pubkey::Pubkey,
-    program_pack::{Pack, IsInitialized},
-    sysvar::{rent::Rent, Sysvar},
-};
-
-// Token program state
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct CalToken {
-    pub is_initialized: bool,
-    pub supply: u64,
-    pub decimals: u8,
-    pub mint_authority: Pubkey,
-}
-
-// Program entry point
-entrypoint!(process_instruction);
-
-// Program logic
-pub fn process_instruction(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    msg!("CAL Token contract: process instruction");
-    
-    // Parse the instruction
-    let instruction = TokenInstruction::unpack(instruction_data)?;
-    
-    // Process the instruction
-    match instruction {
-        TokenInstruction::InitializeMint => {
-            msg!("Instruction: InitializeMint");
-            process_initialize_mint(program_id, accounts)
-        }
-        TokenInstruction::MintTo => {
-            msg!("Instruction: MintTo");
-            process_mint_to(program_id, accounts, amount)
-        }
-        TokenInstruction::Transfer => {
-            msg!("Instruction: Transfer");
-            process_transfer(program_id, accounts, amount)
-        }
-    }
-}
-
-// Initialize a new token mint
-fn process_initialize_mint(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
-    
-    // Get accounts
-    let mint_account = next_account_info(account_info_iter)?;
-    let mint_authority = next_account_info(account_info_iter)?;
-    let rent_account = next_account_info(account_info_iter)?;
-    
-    // Verify the mint account is owned by this program
-    if mint_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-    
-    // Create new token with initial supply
-    let cal_token = CalToken {
-        is_initialized: true,
-        supply: 1_000_000_000, // 1 billion tokens
-        decimals: 9,
-        mint_authority: *mint_authority.key,
-    };
-    
-    // Save state
-    cal_token.pack_into_slice(&mut mint_account.data.borrow_mut());
-    
-    msg!("CAL Token initialized successfully");
-    Ok(())
-}
-
-// Other functions would be implemented here:
-// process_mint_to, process_transfer, etc.
-
-// Instruction types
-enum TokenInstruction {
-    InitializeMint,
-    MintTo,
-    Transfer,
-}
-
-impl TokenInstruction {
-    // Unpacks a byte buffer into a TokenInstruction
-    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        let (&tag, _) = input.split_first().ok_or(ProgramError::InvalidInstructionData)?;
-        
-        Ok(match tag {
-            0 => Self::InitializeMint,
-            1 => Self::MintTo,
-            2 => Self::Transfer,
-            _ => return Err(ProgramError::InvalidInstructionData),
-        })
-    }
-} 
\ No newline at end of file
@@ -0,0 +1,135 @@
+// Eligibility and weighted cost of a (request, resource) pair
+
+use calctra_contracts::accounts::{ComputationRequest, RequestStatus, ResourceAccount};
+use calctra_contracts::resource_vector::{Dimension, ResourceVector};
+use calctra_contracts::taxonomy::{CapabilitySet, Locality, RegionCode, RegionScope};
+
+use crate::strategy::{RequestEntry, ResourceEntry};
+
+// Every cost term is normalized to 0..=TERM_SCALE before weighting
+pub const TERM_SCALE: u64 = 1_000;
+
+// Net successful completions after which a provider's reputation carries no cost
+pub const REPUTATION_SATURATION: i64 = 100;
+
+// Relative weight of each cost term. Lower total cost is a better match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostWeights {
+    // Price relative to the most the requester is willing to pay
+    pub price: u64,
+    // Distance of the provider's reputation from REPUTATION_SATURATION
+    pub reputation: u64,
+    // Distance from the requester's preferred region
+    pub location: u64,
+    // Free capacity a match would leave idle on the resource
+    pub capacity_fit: u64,
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        Self {
+            price: 4,
+            reputation: 2,
+            location: 2,
+            capacity_fit: 1,
+        }
+    }
+}
+
+// Hard constraints a pair must meet, mirroring the checks batch_match and stable_match
+// run before applying a pair. The matcher passes no benchmark or TEE attestation
+// accounts, so requests that need either are never eligible here.
+pub fn is_eligible(request: &RequestEntry, resource: &ResourceEntry) -> bool {
+    let (request_data, resource_data) = (&request.data, &resource.data);
+    let free = match resource_data.free_capacity() {
+        Some(free) => free,
+        None => return false,
+    };
+    let requirements = ResourceVector(request_data.requirements);
+
+    request_data.status == RequestStatus::Pending
+        // A resource that turned the request down is not offered it again
+        && request_data.declined_resource != Some(resource.address)
+        && resource_data.is_active
+        && resource_data.price_per_unit <= request_data.max_price_per_unit
+        && request_data.residency.is_none_or(|code| {
+            RegionScope::try_from(code).is_ok_and(|residency| {
+                residency.contains(RegionCode(resource_data.region), resource_data.zone)
+            })
+        })
+        && request_data
+            .required_accelerator
+            .is_none_or(|accelerator| accelerator == resource_data.accelerator)
+        && request_data
+            .required_cpu_architecture
+            .is_none_or(|architecture| architecture == resource_data.cpu_architecture)
+        && CapabilitySet(resource_data.capabilities)
+            .missing(CapabilitySet(request_data.required_capabilities))
+            .is_empty()
+        && request_data.required_tee.is_none()
+        && requirements.benchmarks() == ResourceVector::default()
+        && free.covers(&requirements.consumable()).is_ok()
+}
+
+// Weighted cost of an eligible pair
+pub fn pair_cost(
+    weights: &CostWeights,
+    request: &ComputationRequest,
+    resource: &ResourceAccount,
+) -> u64 {
+    let price = scaled(resource.price_per_unit, request.max_price_per_unit);
+    let reputation = scaled(
+        (REPUTATION_SATURATION - resource.reputation_score.clamp(0, REPUTATION_SATURATION)) as u64,
+        REPUTATION_SATURATION as u64,
+    );
+    // Requests without a preferred region are indifferent to location
+    let location = match request.preferred_region.map(|preferred| {
+        Locality::between(
+            RegionCode(preferred),
+            request.preferred_zone,
+            RegionCode(resource.region),
+            resource.zone,
+        )
+    }) {
+        None | Some(Locality::SameZone) => 0,
+        Some(Locality::SameCountry) => TERM_SCALE / 3,
+        Some(Locality::SameContinent) => TERM_SCALE * 2 / 3,
+        Some(Locality::Remote) => TERM_SCALE,
+    };
+
+    [
+        (weights.price, price),
+        (weights.reputation, reputation),
+        (weights.location, location),
+        (weights.capacity_fit, capacity_fit(request, resource)),
+    ]
+    .iter()
+    .fold(0u64, |total, &(weight, term)| {
+        total.saturating_add(weight.saturating_mul(term))
+    })
+}
+
+// Share of the free capacity a match would leave idle, averaged over the dimensions the
+// request uses. Tight fits keep large resources available for large requests.
+fn capacity_fit(request: &ComputationRequest, resource: &ResourceAccount) -> u64 {
+    let requirements = ResourceVector(request.requirements).consumable();
+    let free = resource.free_capacity().unwrap_or_default();
+
+    let (total, count) = Dimension::ALL
+        .iter()
+        .filter(|&&dimension| requirements.get(dimension) > 0)
+        .fold((0, 0), |(total, count), &dimension| {
+            let idle = free
+                .get(dimension)
+                .saturating_sub(requirements.get(dimension));
+            (total + scaled(idle, free.get(dimension)), count + 1)
+        });
+    total.checked_div(count).unwrap_or(0)
+}
+
+fn scaled(value: u64, max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    (value.min(max) as u128 * TERM_SCALE as u128 / max as u128) as u64
+}
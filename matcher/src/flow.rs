@@ -0,0 +1,95 @@
+// Minimum-cost flow by successive shortest augmenting paths. Paths are found with a
+// queue-based Bellman-Ford search, since residual edges carry negative costs.
+
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    to: usize,
+    capacity: i64,
+    cost: i64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FlowNetwork {
+    // Edges are stored in pairs, each followed by its residual twin
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
+        }
+    }
+
+    // Add a directed edge and return its index
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: i64, cost: i64) -> usize {
+        let index = self.edges.len();
+        self.edges.push(Edge { to, capacity, cost });
+        self.edges.push(Edge {
+            to: from,
+            capacity: 0,
+            cost: -cost,
+        });
+        self.adjacency[from].push(index);
+        self.adjacency[to].push(index + 1);
+        index
+    }
+
+    // Flow routed through an edge
+    pub fn flow(&self, edge: usize) -> i64 {
+        self.edges[edge ^ 1].capacity
+    }
+
+    // Send up to `limit` units from source to sink at the least total cost. Returns the
+    // flow sent and its cost.
+    pub fn min_cost_flow(&mut self, source: usize, sink: usize, limit: i64) -> (i64, i64) {
+        let nodes = self.adjacency.len();
+        let (mut flow, mut cost) = (0, 0);
+
+        while flow < limit {
+            // Cheapest path in the residual network, as the edge reaching each node
+            let mut distance = vec![i64::MAX; nodes];
+            let mut via = vec![usize::MAX; nodes];
+            let mut queued = vec![false; nodes];
+            let mut queue = VecDeque::from([source]);
+            distance[source] = 0;
+            while let Some(node) = queue.pop_front() {
+                queued[node] = false;
+                for &edge in &self.adjacency[node] {
+                    let Edge { to, capacity, cost } = self.edges[edge];
+                    if capacity > 0 && distance[node] + cost < distance[to] {
+                        distance[to] = distance[node] + cost;
+                        via[to] = edge;
+                        if !queued[to] {
+                            queued[to] = true;
+                            queue.push_back(to);
+                        }
+                    }
+                }
+            }
+            if distance[sink] == i64::MAX {
+                break;
+            }
+
+            let mut push = limit - flow;
+            let mut node = sink;
+            while node != source {
+                push = push.min(self.edges[via[node]].capacity);
+                node = self.edges[via[node] ^ 1].to;
+            }
+            let mut node = sink;
+            while node != source {
+                self.edges[via[node]].capacity -= push;
+                self.edges[via[node] ^ 1].capacity += push;
+                node = self.edges[via[node] ^ 1].to;
+            }
+            flow += push;
+            cost += push * distance[sink];
+        }
+        (flow, cost)
+    }
+}
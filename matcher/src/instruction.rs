@@ -0,0 +1,106 @@
+// Batch match instructions for the Anchor resource matching program
+
+use solana_program::hash::hash;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::strategy::{Assignment, RequestEntry, ResourceEntry};
+
+// Pairs per instruction. Must match MAX_BATCH_PAIRS in the resource matching program.
+pub const MAX_BATCH_PAIRS: usize = 8;
+
+// Requests plus resources in one stable matching. Must match MAX_STABLE_PARTICIPANTS in
+// the resource matching program.
+pub const MAX_STABLE_PARTICIPANTS: usize = 16;
+
+// Build batch_match instructions for an assignment, MAX_BATCH_PAIRS pairs per
+// instruction. Each instruction is applied all-or-nothing on chain, so they should be
+// sent as separate transactions. Full batches exceed the legacy transaction size and
+// need an address lookup table holding the request and resource accounts.
+//
+// The matcher must be the system authority, unless every resource in a batch belongs
+// to the matcher. Matches the system authority makes are only offered, and each
+// provider has to accept them before they time out.
+pub fn batch_match_instructions(
+    program_id: &Pubkey,
+    system_state: &Pubkey,
+    matcher: &Pubkey,
+    assignments: &[Assignment],
+) -> Vec<Instruction> {
+    let discriminator = &hash(b"global:batch_match").to_bytes()[..8];
+
+    assignments
+        .chunks(MAX_BATCH_PAIRS)
+        .map(|batch| {
+            let mut accounts = vec![
+                AccountMeta::new(*system_state, false),
+                AccountMeta::new_readonly(*matcher, true),
+            ];
+            // Pairs carry no benchmark or TEE attestation accounts
+            let mut data = discriminator.to_vec();
+            data.extend_from_slice(&(batch.len() as u32).to_le_bytes());
+            for assignment in batch {
+                accounts.push(AccountMeta::new(assignment.request, false));
+                accounts.push(AccountMeta::new(assignment.resource, false));
+                data.extend_from_slice(&[0, 0]);
+            }
+
+            Instruction {
+                program_id: *program_id,
+                accounts,
+                data,
+            }
+        })
+        .collect()
+}
+
+// Build the stable_match instruction for an assignment over the given participants.
+// Every request and resource is passed with its preferences PDA, since the program only
+// rules out blocking pairs among the accounts it is given. Returns None if there are
+// too many participants or an assignment names an account outside them.
+pub fn stable_match_instruction(
+    program_id: &Pubkey,
+    system_state: &Pubkey,
+    matcher: &Pubkey,
+    requests: &[RequestEntry],
+    resources: &[ResourceEntry],
+    assignments: &[Assignment],
+) -> Option<Instruction> {
+    if requests.len() + resources.len() > MAX_STABLE_PARTICIPANTS {
+        return None;
+    }
+
+    let mut data = hash(b"global:stable_match").to_bytes()[..8].to_vec();
+    data.push(requests.len() as u8);
+    data.extend_from_slice(&(assignments.len() as u32).to_le_bytes());
+    for assignment in assignments {
+        let request = requests
+            .iter()
+            .position(|request| request.address == assignment.request)?;
+        let resource = resources
+            .iter()
+            .position(|resource| resource.address == assignment.resource)?;
+        data.extend_from_slice(&[request as u8, resource as u8]);
+    }
+
+    let mut accounts = vec![
+        AccountMeta::new(*system_state, false),
+        AccountMeta::new_readonly(*matcher, true),
+    ];
+    let participants = requests
+        .iter()
+        .map(|request| request.address)
+        .chain(resources.iter().map(|resource| resource.address));
+    for participant in participants {
+        let (preferences, _) =
+            Pubkey::find_program_address(&[b"preferences", participant.as_ref()], program_id);
+        accounts.push(AccountMeta::new(participant, false));
+        accounts.push(AccountMeta::new_readonly(preferences, false));
+    }
+
+    Some(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
@@ -0,0 +1,21 @@
+// Off-chain matching engine for Calctra.
+// A matcher decodes the pending computation requests and available resources of the
+// Anchor resource matching program, picks an assignment with a MatchStrategy and
+// submits it as batch match instructions signed by the system authority. The on-chain
+// program re-checks every pair, so strategies only decide which of the eligible pairs
+// to apply.
+
+pub mod cost;
+pub mod flow;
+pub mod instruction;
+pub mod optimal;
+pub mod stable;
+pub mod strategy;
+
+pub use cost::CostWeights;
+pub use instruction::{
+    batch_match_instructions, stable_match_instruction, MAX_BATCH_PAIRS, MAX_STABLE_PARTICIPANTS,
+};
+pub use optimal::OptimalAssignment;
+pub use stable::StableMatching;
+pub use strategy::{Assignment, MatchStrategy, RequestEntry, ResourceEntry};
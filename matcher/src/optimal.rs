@@ -0,0 +1,267 @@
+// Globally cheapest assignment of requests to resources
+
+use calctra_contracts::resource_vector::{Dimension, ResourceVector};
+
+use crate::cost::{is_eligible, pair_cost, CostWeights};
+use crate::flow::FlowNetwork;
+use crate::strategy::{Assignment, MatchStrategy, RequestEntry, ResourceEntry};
+
+// Subproblems the search solves before settling for the best assignment found so far
+pub const MAX_SEARCH_NODES: usize = 1_000;
+
+// Matches as many requests as possible, and among those assignments minimizes the total
+// weighted cost.
+//
+// A resource takes several requests as long as their requirements fit its free capacity
+// in every dimension. The solve relaxes that to a limit on how many requests each
+// resource holds, which is a min-cost flow. A relaxed solution that also fits every
+// resource's capacity is optimal. Otherwise one of the requests on an oversubscribed
+// resource has to move, and the search branches on which, skipping branches whose
+// relaxation cannot beat the best assignment found so far. The result is optimal unless
+// the search exceeds MAX_SEARCH_NODES subproblems.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptimalAssignment {
+    pub weights: CostWeights,
+}
+
+impl OptimalAssignment {
+    pub fn new(weights: CostWeights) -> Self {
+        Self { weights }
+    }
+}
+
+// Requests and resources by index. An assignment gives each request its resource, if any.
+struct Problem {
+    costs: Vec<Vec<Option<u64>>>,
+    demands: Vec<ResourceVector>,
+    free: Vec<ResourceVector>,
+    // Divisor bringing pair costs into the range the flow network can sum
+    scale: u64,
+    // Cost of leaving a request unmatched, more than any set of pairs can save
+    unmatched: i64,
+}
+
+struct Search {
+    best: Vec<Option<usize>>,
+    best_cost: i64,
+    nodes: usize,
+}
+
+impl Problem {
+    // Pair cost as the flow network sees it
+    fn flow_cost(&self, cost: u64) -> i64 {
+        (cost / self.scale) as i64
+    }
+
+    // Most eligible requests the resource could hold at once. Taking the smallest
+    // demands first bounds the count in each dimension.
+    fn slot_bound(&self, resource: usize) -> usize {
+        let candidates: Vec<&ResourceVector> = (0..self.costs.len())
+            .filter(|&request| self.costs[request][resource].is_some())
+            .map(|request| &self.demands[request])
+            .collect();
+
+        Dimension::ALL
+            .iter()
+            .map(|&dimension| {
+                let mut demands: Vec<u64> = candidates
+                    .iter()
+                    .map(|demand| demand.get(dimension))
+                    .collect();
+                demands.sort_unstable();
+                let mut used = 0u64;
+                demands
+                    .iter()
+                    .take_while(|&&demand| {
+                        used = used.saturating_add(demand);
+                        used <= self.free[resource].get(dimension)
+                    })
+                    .count()
+            })
+            .min()
+            .unwrap_or(candidates.len())
+    }
+
+    // Cheapest assignment that respects the slot bounds, and its cost
+    fn relax(&self) -> (Vec<Option<usize>>, i64) {
+        let (requests, resources) = (self.costs.len(), self.free.len());
+        let source = 0;
+        let sink = requests + resources + 1;
+        let mut network = FlowNetwork::new(requests + resources + 2);
+
+        let mut pair_edges = Vec::new();
+        for (request, row) in self.costs.iter().enumerate() {
+            network.add_edge(source, 1 + request, 1, 0);
+            network.add_edge(1 + request, sink, 1, self.unmatched);
+            for (resource, cost) in row.iter().enumerate() {
+                if let Some(cost) = cost {
+                    let cost = self.flow_cost(*cost);
+                    let edge = network.add_edge(1 + request, 1 + requests + resource, 1, cost);
+                    pair_edges.push((request, resource, edge));
+                }
+            }
+        }
+        for resource in 0..resources {
+            let slots = self.slot_bound(resource) as i64;
+            network.add_edge(1 + requests + resource, sink, slots, 0);
+        }
+
+        let (_, cost) = network.min_cost_flow(source, sink, requests as i64);
+        let mut assignment = vec![None; requests];
+        for (request, resource, edge) in pair_edges {
+            if network.flow(edge) > 0 {
+                assignment[request] = Some(resource);
+            }
+        }
+        (assignment, cost)
+    }
+
+    fn total_cost(&self, assignment: &[Option<usize>]) -> i64 {
+        assignment
+            .iter()
+            .enumerate()
+            .map(|(request, resource)| match resource {
+                Some(resource) => {
+                    self.flow_cost(self.costs[request][*resource].unwrap_or_default())
+                }
+                None => self.unmatched,
+            })
+            .sum()
+    }
+
+    // A resource whose assigned requests do not fit its free capacity, with those requests
+    fn oversubscribed(&self, assignment: &[Option<usize>]) -> Option<(usize, Vec<usize>)> {
+        (0..self.free.len()).find_map(|resource| {
+            let assigned: Vec<usize> = (0..assignment.len())
+                .filter(|&request| assignment[request] == Some(resource))
+                .collect();
+            let fits = assigned
+                .iter()
+                .try_fold(ResourceVector::default(), |total, &request| {
+                    total.checked_add(&self.demands[request])
+                })
+                .is_some_and(|total| self.free[resource].covers(&total).is_ok());
+            (!fits).then_some((resource, assigned))
+        })
+    }
+
+    // Feasible assignment from a relaxed one, where each resource keeps its cheapest
+    // requests while they fit
+    fn repair(&self, assignment: &[Option<usize>]) -> Vec<Option<usize>> {
+        let mut pairs: Vec<(Option<u64>, usize, usize)> = assignment
+            .iter()
+            .enumerate()
+            .filter_map(|(request, resource)| {
+                let resource = (*resource)?;
+                Some((self.costs[request][resource], request, resource))
+            })
+            .collect();
+        pairs.sort_unstable();
+
+        let mut repaired = vec![None; assignment.len()];
+        let mut used = vec![ResourceVector::default(); self.free.len()];
+        for (_, request, resource) in pairs {
+            if let Some(total) = used[resource].checked_add(&self.demands[request]) {
+                if self.free[resource].covers(&total).is_ok() {
+                    used[resource] = total;
+                    repaired[request] = Some(resource);
+                }
+            }
+        }
+        repaired
+    }
+
+    fn search(&mut self, search: &mut Search) {
+        search.nodes += 1;
+        if search.nodes > MAX_SEARCH_NODES {
+            return;
+        }
+
+        let (relaxed, bound) = self.relax();
+        if bound >= search.best_cost {
+            return;
+        }
+        let Some((resource, assigned)) = self.oversubscribed(&relaxed) else {
+            search.best = relaxed;
+            search.best_cost = bound;
+            return;
+        };
+
+        let repaired = self.repair(&relaxed);
+        let repaired_cost = self.total_cost(&repaired);
+        if repaired_cost < search.best_cost {
+            search.best = repaired;
+            search.best_cost = repaired_cost;
+        }
+
+        // Every feasible assignment moves at least one of these requests off the resource
+        for request in assigned {
+            let cost = self.costs[request][resource].take();
+            self.search(search);
+            self.costs[request][resource] = cost;
+        }
+    }
+}
+
+impl MatchStrategy for OptimalAssignment {
+    fn assign(&self, requests: &[RequestEntry], resources: &[ResourceEntry]) -> Vec<Assignment> {
+        let costs: Vec<Vec<Option<u64>>> = requests
+            .iter()
+            .map(|request| {
+                resources
+                    .iter()
+                    .map(|resource| {
+                        is_eligible(request, resource)
+                            .then(|| pair_cost(&self.weights, &request.data, &resource.data))
+                    })
+                    .collect()
+            })
+            .collect();
+        let max_cost = match costs.iter().flatten().flatten().max() {
+            Some(&max_cost) => max_cost,
+            None => return Vec::new(),
+        };
+
+        // A path through the flow network crosses fewer than 2(n + 2) edges, each costing
+        // at most the unmatched cost of (n + 1) times the largest pair cost. Pair costs are
+        // divided down until that fits in an i64, which keeps their order up to ties.
+        let nodes = requests.len() as u64 + 2;
+        let max_flow_cost =
+            (i64::MAX as u64 / nodes.saturating_mul(nodes).saturating_mul(4)).max(1);
+        let scale = max_cost / max_flow_cost + 1;
+        let unmatched = (max_cost / scale + 1) as i64 * (requests.len() as i64 + 1);
+        let mut problem = Problem {
+            costs,
+            demands: requests
+                .iter()
+                .map(|request| ResourceVector(request.data.requirements).consumable())
+                .collect(),
+            free: resources
+                .iter()
+                .map(|resource| resource.data.free_capacity().unwrap_or_default())
+                .collect(),
+            scale,
+            unmatched,
+        };
+        let mut search = Search {
+            best: vec![None; requests.len()],
+            best_cost: unmatched.saturating_mul(requests.len() as i64),
+            nodes: 0,
+        };
+        problem.search(&mut search);
+
+        search
+            .best
+            .iter()
+            .enumerate()
+            .filter_map(|(request, resource)| {
+                let resource = (*resource)?;
+                Some(Assignment {
+                    request: requests[request].address,
+                    resource: resources[resource].address,
+                    cost: problem.costs[request][resource]?,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,131 @@
+// Stable matching that honors the preferences of requesters and providers
+
+use std::collections::BTreeMap;
+
+use solana_program::pubkey::Pubkey;
+
+use crate::cost::{is_eligible, pair_cost, CostWeights};
+use crate::strategy::{Assignment, MatchStrategy, RequestEntry, ResourceEntry};
+
+// Deferred acceptance with requests proposing, one request per resource.
+//
+// Each side ranks the other by its on-chain preference list, keyed here by the request
+// or resource account it belongs to. Entries name a counterpart by its account or its
+// owner's wallet, and unlisted counterparts rank last. Ties are broken by weighted
+// cost, so the result is stable under the preference lists alone, which is what the
+// stable_match instruction checks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StableMatching {
+    pub weights: CostWeights,
+    pub preferences: BTreeMap<Pubkey, Vec<Pubkey>>,
+}
+
+impl StableMatching {
+    pub fn new(weights: CostWeights, preferences: BTreeMap<Pubkey, Vec<Pubkey>>) -> Self {
+        Self {
+            weights,
+            preferences,
+        }
+    }
+
+    fn rank(&self, subject: &Pubkey, account: Pubkey, owner: Pubkey) -> usize {
+        match self.preferences.get(subject) {
+            Some(ranked) => preference_rank(ranked, account, owner),
+            None => 0,
+        }
+    }
+}
+
+impl MatchStrategy for StableMatching {
+    fn assign(&self, requests: &[RequestEntry], resources: &[ResourceEntry]) -> Vec<Assignment> {
+        let costs: Vec<Vec<Option<u64>>> = requests
+            .iter()
+            .map(|request| {
+                resources
+                    .iter()
+                    .map(|resource| {
+                        is_eligible(request, resource)
+                            .then(|| pair_cost(&self.weights, &request.data, &resource.data))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each request proposes to its eligible resources from most to least preferred
+        let proposals: Vec<Vec<usize>> = requests
+            .iter()
+            .enumerate()
+            .map(|(request, entry)| {
+                let mut eligible: Vec<(usize, u64, usize)> = resources
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(resource, candidate)| {
+                        let cost = costs[request][resource]?;
+                        let rank =
+                            self.rank(&entry.address, candidate.address, candidate.data.provider);
+                        Some((rank, cost, resource))
+                    })
+                    .collect();
+                eligible.sort_unstable();
+                eligible
+                    .into_iter()
+                    .map(|(_, _, resource)| resource)
+                    .collect()
+            })
+            .collect();
+
+        // How a resource ranks a proposing request, lower is better
+        let standing = |resource: usize, request: usize| {
+            let entry = &requests[request];
+            let rank = self.rank(
+                &resources[resource].address,
+                entry.address,
+                entry.data.requester,
+            );
+            (rank, costs[request][resource], request)
+        };
+
+        let mut next_proposal = vec![0; requests.len()];
+        let mut holder: Vec<Option<usize>> = vec![None; resources.len()];
+        let mut free: Vec<usize> = (0..requests.len()).rev().collect();
+        while let Some(request) = free.pop() {
+            let resource = match proposals[request].get(next_proposal[request]) {
+                Some(&resource) => resource,
+                None => continue,
+            };
+            next_proposal[request] += 1;
+
+            match holder[resource] {
+                Some(current) if standing(resource, current) < standing(resource, request) => {
+                    free.push(request);
+                }
+                current => {
+                    holder[resource] = Some(request);
+                    free.extend(current);
+                }
+            }
+        }
+
+        holder
+            .iter()
+            .enumerate()
+            .filter_map(|(resource, request)| {
+                let request = (*request)?;
+                Some(Assignment {
+                    request: requests[request].address,
+                    resource: resources[resource].address,
+                    cost: costs[request][resource]?,
+                })
+            })
+            .collect()
+    }
+}
+
+// Position of a counterpart in a preference list, matched by its account or its owner's
+// wallet. Unlisted counterparts share the last rank.
+pub fn preference_rank(ranked: &[Pubkey], account: Pubkey, owner: Pubkey) -> usize {
+    ranked
+        .iter()
+        .position(|entry| *entry == account || *entry == owner)
+        .unwrap_or(ranked.len())
+}
@@ -0,0 +1,52 @@
+// Matching strategies and the decoded accounts they work on
+
+use calctra_contracts::accounts::{
+    AccountDecodeError, AnchorAccount, ComputationRequest, ResourceAccount,
+};
+use solana_program::pubkey::Pubkey;
+
+// A decoded resource account
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceEntry {
+    pub address: Pubkey,
+    pub data: ResourceAccount,
+}
+
+// A decoded computation request account
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestEntry {
+    pub address: Pubkey,
+    pub data: ComputationRequest,
+}
+
+impl ResourceEntry {
+    pub fn decode(address: Pubkey, data: &[u8]) -> Result<Self, AccountDecodeError> {
+        Ok(Self {
+            address,
+            data: ResourceAccount::decode(data)?,
+        })
+    }
+}
+
+impl RequestEntry {
+    pub fn decode(address: Pubkey, data: &[u8]) -> Result<Self, AccountDecodeError> {
+        Ok(Self {
+            address,
+            data: ComputationRequest::decode(data)?,
+        })
+    }
+}
+
+// A request bound to a resource, with the weighted cost the strategy assigned it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Assignment {
+    pub request: Pubkey,
+    pub resource: Pubkey,
+    pub cost: u64,
+}
+
+pub trait MatchStrategy {
+    // Assign pending requests to resources. Requests without an eligible resource are
+    // left out, and the capacity a resource offers is never oversubscribed.
+    fn assign(&self, requests: &[RequestEntry], resources: &[ResourceEntry]) -> Vec<Assignment>;
+}
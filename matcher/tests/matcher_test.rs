@@ -0,0 +1,483 @@
+#[cfg(test)]
+mod tests {
+    use calctra_contracts::accounts::{
+        AccountDecodeError, AnchorAccount, ComputationRequest, RequestStatus, ResourceAccount,
+        TeeRequirement,
+    };
+    use calctra_contracts::resource_vector::{Dimension, ResourceVector};
+    use calctra_contracts::taxonomy::{RegionScope, TEE_MEASUREMENT_LEN};
+    use calctra_matcher::cost::{is_eligible, REPUTATION_SATURATION, TERM_SCALE};
+    use calctra_matcher::flow::FlowNetwork;
+    use calctra_matcher::stable::preference_rank;
+    use calctra_matcher::{
+        batch_match_instructions, stable_match_instruction, Assignment, CostWeights, MatchStrategy,
+        OptimalAssignment, RequestEntry, ResourceEntry, StableMatching, MAX_BATCH_PAIRS,
+    };
+    use solana_program::pubkey::Pubkey;
+    use std::collections::BTreeMap;
+
+    fn cores(count: u64) -> ResourceVector {
+        let mut vector = ResourceVector::default();
+        vector.set(Dimension::CpuCores, count);
+        vector
+    }
+
+    fn resource(cores_available: u64, price_per_unit: u64, region: &[u8; 2]) -> ResourceEntry {
+        ResourceEntry {
+            address: Pubkey::new_unique(),
+            data: ResourceAccount {
+                provider: Pubkey::new_unique(),
+                capacity: cores(cores_available).0,
+                price_per_unit,
+                is_active: true,
+                reputation_score: 80,
+                region: *region,
+                ..ResourceAccount::default()
+            },
+        }
+    }
+
+    fn request(cores_required: u64, max_price_per_unit: u64) -> RequestEntry {
+        RequestEntry {
+            address: Pubkey::new_unique(),
+            data: ComputationRequest {
+                requester: Pubkey::new_unique(),
+                requirements: cores(cores_required).0,
+                max_price_per_unit,
+                preferred_region: Some(*b"DE"),
+                status: RequestStatus::Pending,
+                ..ComputationRequest::default()
+            },
+        }
+    }
+
+    fn price_only() -> OptimalAssignment {
+        OptimalAssignment::new(CostWeights {
+            price: 1,
+            reputation: 0,
+            location: 0,
+            capacity_fit: 0,
+        })
+    }
+
+    fn total_cost(assignments: &[Assignment]) -> u64 {
+        assignments.iter().map(|assignment| assignment.cost).sum()
+    }
+
+    fn resource_of(assignments: &[Assignment], request: &RequestEntry) -> Option<Pubkey> {
+        assignments
+            .iter()
+            .find(|assignment| assignment.request == request.address)
+            .map(|assignment| assignment.resource)
+    }
+
+    #[test]
+    fn test_min_cost_flow_finds_minimum_cost() {
+        // Three workers and three jobs, job 0 able to take two workers
+        let cost = [[4, 1, 3], [2, 0, 5], [3, 2, 2]];
+        let (source, sink) = (0, 7);
+        let mut network = FlowNetwork::new(8);
+        let mut edges = Vec::new();
+        for (worker, row) in cost.iter().enumerate() {
+            network.add_edge(source, 1 + worker, 1, 0);
+            for (job, &cost) in row.iter().enumerate() {
+                edges.push((worker, job, network.add_edge(1 + worker, 4 + job, 1, cost)));
+            }
+        }
+        for (job, capacity) in [2, 1, 1].into_iter().enumerate() {
+            network.add_edge(4 + job, sink, capacity, 0);
+        }
+
+        assert_eq!(network.min_cost_flow(source, sink, 3), (3, 5));
+        let used: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|(_, _, edge)| network.flow(*edge) > 0)
+            .map(|&(worker, job, _)| (worker, job))
+            .collect();
+        assert_eq!(used, vec![(0, 1), (1, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn test_optimal_beats_greedy_choice() {
+        // Each resource holds one request. Greedy would give the cheap resource to the
+        // first request and leave the second with only the expensive one.
+        let cheap = resource(4, 10, b"DE");
+        let expensive = resource(4, 90, b"DE");
+        let flexible = request(4, 100);
+        let tight = request(4, 10);
+        let requests = vec![flexible.clone(), tight.clone()];
+
+        let assignments = price_only().assign(&requests, &[cheap.clone(), expensive.clone()]);
+
+        assert_eq!(assignments.len(), 2);
+        assert_eq!(resource_of(&assignments, &tight), Some(cheap.address));
+        assert_eq!(
+            resource_of(&assignments, &flexible),
+            Some(expensive.address)
+        );
+    }
+
+    #[test]
+    fn test_optimal_packs_several_requests_onto_one_resource() {
+        // Matching one request per resource first would put the large request on the
+        // cheap resource and a small one on the expensive resource. Both small requests
+        // fit on the cheap resource, which matches as many requests for far less.
+        let cheap = resource(8, 10, b"DE");
+        let expensive = resource(4, 90, b"DE");
+        let large = request(8, 100);
+        let small = [request(4, 200), request(4, 200)];
+        let requests = vec![large.clone(), small[0].clone(), small[1].clone()];
+
+        let assignments = price_only().assign(&requests, &[cheap.clone(), expensive]);
+
+        assert_eq!(assignments.len(), 2);
+        assert_eq!(resource_of(&assignments, &large), None);
+        for request in &small {
+            assert_eq!(resource_of(&assignments, request), Some(cheap.address));
+        }
+        assert_eq!(total_cost(&assignments), 100);
+    }
+
+    #[test]
+    fn test_optimal_respects_every_capacity_dimension() {
+        // Two requests fit on the resource by count, but the two cheapest to place do
+        // not fit together
+        let host = resource(8, 10, b"DE");
+        let first = request(5, 100);
+        let second = request(5, 100);
+        let small = request(3, 20);
+        let requests = vec![first.clone(), second.clone(), small.clone()];
+
+        let assignments = price_only().assign(&requests, std::slice::from_ref(&host));
+
+        assert_eq!(assignments.len(), 2);
+        assert_eq!(resource_of(&assignments, &small), Some(host.address));
+        assert_eq!(total_cost(&assignments), 600);
+    }
+
+    #[test]
+    fn test_optimal_handles_extreme_weights() {
+        // The expensive pair costs more than i64::MAX and must still lose to the cheap one
+        let price_heavy = OptimalAssignment::new(CostWeights {
+            price: u64::MAX / TERM_SCALE,
+            reputation: 0,
+            location: 0,
+            capacity_fit: 0,
+        });
+        let cheap = resource(8, 10, b"DE");
+        let expensive = resource(4, 90, b"DE");
+        let job = request(4, 100);
+
+        let assignments = price_heavy.assign(
+            std::slice::from_ref(&job),
+            &[cheap.clone(), expensive.clone()],
+        );
+        assert_eq!(resource_of(&assignments, &job), Some(cheap.address));
+
+        // Every pair cost saturates, yet capacity still decides how many requests fit
+        let saturated = OptimalAssignment::new(CostWeights {
+            price: u64::MAX,
+            reputation: u64::MAX,
+            location: u64::MAX,
+            capacity_fit: u64::MAX,
+        });
+        let requests: Vec<RequestEntry> = (0..4).map(|_| request(4, 100)).collect();
+
+        let assignments = saturated.assign(&requests, &[cheap.clone(), expensive.clone()]);
+        assert_eq!(assignments.len(), 3);
+        let on_cheap = assignments
+            .iter()
+            .filter(|assignment| assignment.resource == cheap.address)
+            .count();
+        assert_eq!(on_cheap, 2);
+    }
+
+    #[test]
+    fn test_ineligible_pairs_are_never_assigned() {
+        let mut outside_eu = resource(8, 10, b"US");
+        outside_eu.data.reputation_score = REPUTATION_SATURATION;
+        let inside_eu = resource(8, 50, b"FR");
+        let mut resident = request(4, 100);
+        resident.data.residency = Some(RegionScope::EuropeanUnion.code());
+        let too_large = request(16, 100);
+
+        let assignments = OptimalAssignment::default().assign(
+            &[resident.clone(), too_large],
+            &[outside_eu, inside_eu.clone()],
+        );
+
+        assert_eq!(
+            assignments
+                .iter()
+                .map(|a| (a.request, a.resource))
+                .collect::<Vec<_>>(),
+            vec![(resident.address, inside_eu.address)]
+        );
+    }
+
+    #[test]
+    fn test_resource_takes_several_requests_within_capacity() {
+        let large = resource(10, 10, b"DE");
+        let requests = vec![request(4, 100), request(4, 100), request(4, 100)];
+
+        let assignments = OptimalAssignment::default().assign(&requests, &[large]);
+
+        // Two requests fit, the third would oversubscribe the resource
+        assert_eq!(assignments.len(), 2);
+    }
+
+    #[test]
+    fn test_tighter_fit_is_preferred() {
+        let large = resource(64, 10, b"DE");
+        let small = resource(4, 10, b"DE");
+        let job = request(4, 100);
+
+        let assignments = OptimalAssignment::default().assign(&[job], &[large, small.clone()]);
+
+        assert_eq!(assignments[0].resource, small.address);
+        assert!(total_cost(&assignments) > 0);
+    }
+
+    #[test]
+    fn test_eligibility_follows_program_rules() {
+        let host = resource(8, 10, b"DE");
+        assert!(is_eligible(&request(4, 100), &host));
+
+        let cases: [fn(&mut RequestEntry, &ResourceEntry); 6] = [
+            |request, _| request.data.status = RequestStatus::Offered,
+            |request, _| request.data.status = RequestStatus::Auctioning,
+            |request, host| request.data.declined_resource = Some(host.address),
+            |request, _| request.data.max_price_per_unit = 9,
+            |request, _| request.data.required_accelerator = Some(1),
+            |request, _| {
+                request.data.required_tee = Some(TeeRequirement {
+                    platform: 0,
+                    measurement: [0; TEE_MEASUREMENT_LEN],
+                })
+            },
+        ];
+        for change in cases {
+            let mut candidate = request(4, 100);
+            change(&mut candidate, &host);
+            assert!(!is_eligible(&candidate, &host));
+        }
+
+        // Benchmark thresholds need an attestation the matcher does not pass
+        let mut benchmarked = request(4, 100);
+        benchmarked.data.requirements[Dimension::ComputationPower as usize] = 1;
+        assert!(!is_eligible(&benchmarked, &host));
+
+        let mut retired = host.clone();
+        retired.data.is_active = false;
+        assert!(!is_eligible(&request(4, 100), &retired));
+
+        let mut full = host;
+        full.data.allocated = cores(6).0;
+        assert!(!is_eligible(&request(4, 100), &full));
+    }
+
+    #[test]
+    fn test_entries_decode_anchor_accounts() {
+        let host = resource(8, 10, b"DE");
+        let job = request(4, 100);
+
+        // Accounts are allocated larger than their contents
+        let mut data = host.data.encode();
+        data.resize(data.len() + 128, 0);
+        assert_eq!(ResourceEntry::decode(host.address, &data), Ok(host.clone()));
+        assert_eq!(
+            RequestEntry::decode(job.address, &job.data.encode()),
+            Ok(job)
+        );
+        assert_eq!(
+            RequestEntry::decode(host.address, &data),
+            Err(AccountDecodeError::WrongAccountType)
+        );
+    }
+
+    #[test]
+    fn test_batch_match_instructions() {
+        let program_id = Pubkey::new_unique();
+        let system_state = Pubkey::new_unique();
+        let matcher = Pubkey::new_unique();
+        let assignments: Vec<Assignment> = (0..MAX_BATCH_PAIRS + 1)
+            .map(|_| Assignment {
+                request: Pubkey::new_unique(),
+                resource: Pubkey::new_unique(),
+                cost: 0,
+            })
+            .collect();
+
+        let instructions =
+            batch_match_instructions(&program_id, &system_state, &matcher, &assignments);
+
+        assert_eq!(instructions.len(), 2);
+        let first = &instructions[0];
+        assert_eq!(first.program_id, program_id);
+        assert_eq!(first.accounts.len(), 2 + 2 * MAX_BATCH_PAIRS);
+        assert!(first.accounts[1].is_signer);
+        assert_eq!(first.accounts[2].pubkey, assignments[0].request);
+        assert!(first.accounts[3].is_writable);
+        assert_eq!(first.data.len(), 8 + 4 + 2 * MAX_BATCH_PAIRS);
+        assert_eq!(&first.data[8..12], &(MAX_BATCH_PAIRS as u32).to_le_bytes());
+        assert_eq!(instructions[1].accounts.len(), 4);
+    }
+
+    // A request and resource that are eligible for each other and both strictly prefer
+    // each other to what the matching gave them
+    fn blocking_pairs(
+        preferences: &BTreeMap<Pubkey, Vec<Pubkey>>,
+        requests: &[RequestEntry],
+        resources: &[ResourceEntry],
+        assignments: &[Assignment],
+    ) -> Vec<(Pubkey, Pubkey)> {
+        let rank = |subject: &Pubkey, account: Pubkey, owner: Pubkey| {
+            preference_rank(
+                preferences.get(subject).map_or(&[][..], Vec::as_slice),
+                account,
+                owner,
+            )
+        };
+        let matched_resource = |request: &RequestEntry| {
+            assignments
+                .iter()
+                .find(|assignment| assignment.request == request.address)
+                .and_then(|assignment| resources.iter().find(|r| r.address == assignment.resource))
+        };
+        let matched_request = |resource: &ResourceEntry| {
+            assignments
+                .iter()
+                .find(|assignment| assignment.resource == resource.address)
+                .and_then(|assignment| requests.iter().find(|r| r.address == assignment.request))
+        };
+
+        let mut blocking = Vec::new();
+        for request in requests {
+            for resource in resources {
+                if !is_eligible(request, resource) {
+                    continue;
+                }
+                let resource_rank =
+                    |r: &ResourceEntry| rank(&request.address, r.address, r.data.provider);
+                let request_rank =
+                    |q: &RequestEntry| rank(&resource.address, q.address, q.data.requester);
+                let request_prefers = match matched_resource(request) {
+                    Some(current) if current.address == resource.address => continue,
+                    Some(current) => resource_rank(resource) < resource_rank(current),
+                    None => true,
+                };
+                let resource_prefers = match matched_request(resource) {
+                    Some(current) => request_rank(request) < request_rank(current),
+                    None => true,
+                };
+                if request_prefers && resource_prefers {
+                    blocking.push((request.address, resource.address));
+                }
+            }
+        }
+        blocking
+    }
+
+    #[test]
+    fn test_stable_matching_honors_both_sides() {
+        let lab = request(4, 100);
+        let institute = request(4, 90);
+        let cheap = resource(4, 10, b"DE");
+        let expensive = resource(4, 90, b"DE");
+
+        // Both labs want the expensive resource, whose provider prefers the institute
+        let mut preferences = BTreeMap::new();
+        preferences.insert(lab.address, vec![expensive.data.provider]);
+        preferences.insert(institute.address, vec![expensive.address]);
+        preferences.insert(expensive.address, vec![institute.data.requester]);
+        let requests = vec![lab.clone(), institute.clone()];
+        let resources = vec![cheap.clone(), expensive.clone()];
+
+        let strategy = StableMatching::new(CostWeights::default(), preferences.clone());
+        let assignments = strategy.assign(&requests, &resources);
+
+        let mut pairs: Vec<_> = assignments
+            .iter()
+            .map(|a| (a.request, a.resource))
+            .collect();
+        pairs.sort();
+        let mut expected = vec![
+            (institute.address, expensive.address),
+            (lab.address, cheap.address),
+        ];
+        expected.sort();
+        assert_eq!(pairs, expected);
+        assert!(blocking_pairs(&preferences, &requests, &resources, &assignments).is_empty());
+
+        // The cheapest assignment ignores the provider and leaves a blocking pair
+        let cheapest = OptimalAssignment::default().assign(&requests, &resources);
+        assert_eq!(
+            blocking_pairs(&preferences, &requests, &resources, &cheapest),
+            vec![(institute.address, expensive.address)]
+        );
+    }
+
+    #[test]
+    fn test_stable_matching_without_preferences_is_stable() {
+        let requests: Vec<RequestEntry> = (0..4).map(|i| request(2 + i, 100)).collect();
+        let resources: Vec<ResourceEntry> = (0..3)
+            .map(|i| resource(4 + 2 * i, 20 * (i + 1), b"DE"))
+            .collect();
+
+        let strategy = StableMatching::default();
+        let assignments = strategy.assign(&requests, &resources);
+
+        assert_eq!(assignments.len(), 3);
+        assert!(
+            blocking_pairs(&strategy.preferences, &requests, &resources, &assignments).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_stable_match_instruction() {
+        let program_id = Pubkey::new_unique();
+        let requests = vec![request(4, 100), request(4, 100)];
+        let resources = vec![resource(4, 10, b"DE")];
+        let assignments = vec![Assignment {
+            request: requests[1].address,
+            resource: resources[0].address,
+            cost: 0,
+        }];
+
+        let instruction = stable_match_instruction(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &requests,
+            &resources,
+            &assignments,
+        )
+        .unwrap();
+
+        // Every participant is followed by its preferences PDA
+        assert_eq!(instruction.accounts.len(), 2 + 2 * 3);
+        let (preferences, _) = Pubkey::find_program_address(
+            &[b"preferences", resources[0].address.as_ref()],
+            &program_id,
+        );
+        assert_eq!(instruction.accounts[6].pubkey, resources[0].address);
+        assert_eq!(instruction.accounts[7].pubkey, preferences);
+        assert_eq!(instruction.data[8], 2);
+        assert_eq!(&instruction.data[9..], &[1, 0, 0, 0, 1, 0]);
+
+        // Assignments must stay within the participants
+        let outsider = Assignment {
+            request: Pubkey::new_unique(),
+            ..assignments[0]
+        };
+        assert!(stable_match_instruction(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &requests,
+            &resources,
+            &[outsider],
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,962 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::{instructions, Sysvar},
+};
+use thiserror::Error;
+
+use calctra_contracts::attestation::{ResultAttestation, MAX_WORKER_KEYS};
+use calctra_contracts::resource_vector::ResourceVector;
+use calctra_contracts::taxonomy::{
+    AcceleratorModel, CapabilitySet, CpuArchitecture, JobCategory, RegionCode, RegionScope,
+};
+
+// Seed of the program config PDA that holds the system authority
+pub const CONFIG_SEED: &[u8] = b"config";
+
+// Borsh-encoded sizes of the variable-length fields, at their largest
+const OPTION_TAG_LEN: usize = 1;
+const VEC_LEN_PREFIX: usize = 4;
+const REGION_SCOPE_MAX_LEN: usize = 1 + 3;
+
+// Kind of account, stored in the first byte of every account this program owns so
+// that a resource, a request and the config can never be mistaken for each other
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountType {
+    #[default]
+    Uninitialized = 0,
+    Config = 1,
+    Resource = 2,
+    Request = 3,
+}
+
+// Program config, a PDA at CONFIG_SEED
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    pub account_type: AccountType,
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const LEN: usize = 1 + 32 + 1;
+}
+
+// Resource matching program state
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ResourceData {
+    pub account_type: AccountType,
+    pub provider: Pubkey,
+    pub capacity: ResourceVector,
+    pub allocated: ResourceVector,
+    pub capabilities: CapabilitySet,
+    pub cpu_architecture: CpuArchitecture,
+    pub gpu_model: AcceleratorModel,
+    pub price_per_hour: u64,
+    pub availability: bool,
+    pub reputation_score: u8,
+    pub location_code: RegionCode,
+    pub zone: u8,
+    pub worker_keys: Vec<Pubkey>,
+    pub encryption_key: [u8; 32],
+    pub encryption_key_epoch: u32,
+    pub previous_encryption_key: [u8; 32],
+}
+
+impl ResourceData {
+    // Space for a resource with the maximum number of worker keys
+    pub const LEN: usize = 1
+        + 32
+        + ResourceVector::LEN
+        + ResourceVector::LEN
+        + 16
+        + 1
+        + 1
+        + 8
+        + 1
+        + 1
+        + 2
+        + 1
+        + VEC_LEN_PREFIX + 32 * MAX_WORKER_KEYS
+        + 32
+        + 4
+        + 32;
+}
+
+// Computation request state
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ComputationRequest {
+    pub account_type: AccountType,
+    pub requester: Pubkey,
+    pub job_category: JobCategory,
+    pub cpu_architecture: Option<CpuArchitecture>,
+    pub requirements: ResourceVector,
+    pub required_capabilities: CapabilitySet,
+    pub optional_capabilities: CapabilitySet,
+    pub gpu_model: Option<AcceleratorModel>,
+    pub max_price_per_hour: u64,
+    pub preferred_location: RegionCode,
+    pub preferred_zone: u8,
+    pub residency: Option<RegionScope>,
+    pub min_reputation: u8,
+    pub status: RequestStatus,
+    pub matched_provider: Option<Pubkey>,
+    pub matched_resource: Option<Pubkey>,
+    pub result_hash: Option<[u8; 32]>,
+}
+
+impl ComputationRequest {
+    pub const LEN: usize = 1
+        + 32
+        + 1
+        + OPTION_TAG_LEN + 1
+        + ResourceVector::LEN
+        + 16
+        + 16
+        + OPTION_TAG_LEN + 1
+        + 8
+        + 2
+        + 1
+        + OPTION_TAG_LEN + REGION_SCOPE_MAX_LEN
+        + 1
+        + 1
+        + OPTION_TAG_LEN + 32
+        + OPTION_TAG_LEN + 32
+        + OPTION_TAG_LEN + 32;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum RequestStatus {
+    #[default]
+    Pending,
+    Matched,
+    InProgress,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// Provider-supplied fields of a resource, used to register and update it
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ResourceParams {
+    pub capacity: ResourceVector,
+    pub capabilities: CapabilitySet,
+    pub cpu_architecture: CpuArchitecture,
+    pub gpu_model: AcceleratorModel,
+    pub price_per_hour: u64,
+    pub availability: bool,
+    pub location_code: RegionCode,
+    pub zone: u8,
+}
+
+// Requester-supplied fields of a computation request
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct RequestParams {
+    pub job_category: JobCategory,
+    pub cpu_architecture: Option<CpuArchitecture>,
+    pub requirements: ResourceVector,
+    pub required_capabilities: CapabilitySet,
+    pub optional_capabilities: CapabilitySet,
+    pub gpu_model: Option<AcceleratorModel>,
+    pub max_price_per_hour: u64,
+    pub preferred_location: RegionCode,
+    pub preferred_zone: u8,
+    pub residency: Option<RegionScope>,
+    pub min_reputation: u8,
+}
+
+// Resource matching errors
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum MatchingError {
+    #[error("Resource location violates the request's data residency constraint")]
+    ResidencyViolation,
+    #[error("Resource has insufficient capacity")]
+    InsufficientCapacity,
+    #[error("Released more capacity than was allocated")]
+    AllocationUnderflow,
+    #[error("Resource does not match the request")]
+    ResourceMismatch,
+    #[error("Resource is missing a required capability")]
+    MissingCapability,
+    #[error("Resource already has the maximum number of worker keys")]
+    TooManyWorkerKeys,
+    #[error("Worker key is not enrolled for this resource")]
+    WorkerKeyNotFound,
+    #[error("Invalid worker result attestation")]
+    InvalidResultAttestation,
+    #[error("Result attestation was not signed by an enrolled worker key")]
+    UnknownWorkerKey,
+    #[error("Encryption key must be non-zero and differ from the current key")]
+    InvalidEncryptionKey,
+    #[error("Resource is not available")]
+    ResourceUnavailable,
+    #[error("Resource does not have the requested accelerator model")]
+    AcceleratorMismatch,
+    #[error("Resource does not have the requested CPU architecture")]
+    ArchitectureMismatch,
+    #[error("Resource price exceeds the request's maximum")]
+    PriceTooHigh,
+    #[error("Resource reputation is below the request's minimum")]
+    ReputationTooLow,
+    #[error("Signer is not the requester, the provider or the system authority")]
+    UnauthorizedMatcher,
+}
+
+impl From<MatchingError> for ProgramError {
+    fn from(e: MatchingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// Program entry point
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+// Program logic
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    msg!("Resource Matching contract: process instruction");
+
+    // Parse the instruction
+    let instruction = MatchingInstruction::unpack(instruction_data)?;
+
+    // Process the instruction
+    match instruction {
+        MatchingInstruction::RegisterResource(params) => {
+            msg!("Instruction: RegisterResource");
+            process_register_resource(program_id, accounts, params)
+        }
+        MatchingInstruction::UpdateResource(params) => {
+            msg!("Instruction: UpdateResource");
+            process_update_resource(program_id, accounts, params)
+        }
+        MatchingInstruction::SubmitRequest(params) => {
+            msg!("Instruction: SubmitRequest");
+            process_submit_request(program_id, accounts, params)
+        }
+        MatchingInstruction::MatchRequest => {
+            msg!("Instruction: MatchRequest");
+            process_match_request(program_id, accounts)
+        }
+        MatchingInstruction::CompleteRequest { success, result_hash, runtime } => {
+            msg!("Instruction: CompleteRequest");
+            process_complete_request(program_id, accounts, success, result_hash, runtime)
+        }
+        MatchingInstruction::CancelRequest => {
+            msg!("Instruction: CancelRequest");
+            process_cancel_request(program_id, accounts)
+        }
+        MatchingInstruction::AddWorkerKey { worker_key } => {
+            msg!("Instruction: AddWorkerKey");
+            process_add_worker_key(program_id, accounts, worker_key)
+        }
+        MatchingInstruction::RemoveWorkerKey { worker_key } => {
+            msg!("Instruction: RemoveWorkerKey");
+            process_remove_worker_key(program_id, accounts, worker_key)
+        }
+        MatchingInstruction::RotateEncryptionKey { encryption_key } => {
+            msg!("Instruction: RotateEncryptionKey");
+            process_rotate_encryption_key(program_id, accounts, encryption_key)
+        }
+        MatchingInstruction::InitializeConfig => {
+            msg!("Instruction: InitializeConfig");
+            process_initialize_config(program_id, accounts)
+        }
+        MatchingInstruction::SetReputation { reputation_score } => {
+            msg!("Instruction: SetReputation");
+            process_set_reputation(program_id, accounts, reputation_score)
+        }
+    }
+}
+
+// Create the config PDA and make the payer the system authority
+fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let config_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+
+    if !authority_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !system_program::check_id(system_program_account.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (config_key, bump) = Pubkey::find_program_address(&[CONFIG_SEED], program_id);
+    if *config_account.key != config_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Creating an account that already exists fails, so the config is set only once
+    invoke_signed(
+        &system_instruction::create_account(
+            authority_account.key,
+            config_account.key,
+            Rent::get()?.minimum_balance(Config::LEN),
+            Config::LEN as u64,
+            program_id,
+        ),
+        &[
+            authority_account.clone(),
+            config_account.clone(),
+            system_program_account.clone(),
+        ],
+        &[&[CONFIG_SEED, &[bump]]],
+    )?;
+
+    let config = Config {
+        account_type: AccountType::Config,
+        authority: *authority_account.key,
+        bump,
+    };
+    save_account(&config, config_account)?;
+
+    msg!("Config initialized with authority {}", authority_account.key);
+    Ok(())
+}
+
+// Register a new computational resource in an account the provider created for
+// this program with ResourceData::LEN bytes
+fn process_register_resource(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: ResourceParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let resource_account = next_account_info(account_info_iter)?;
+    let provider_account = next_account_info(account_info_iter)?;
+
+    // Verify provider is signer
+    if !provider_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_uninitialized(program_id, resource_account, ResourceData::LEN)?;
+    validate_resource_params(&params)?;
+
+    // Initialize resource data
+    let data = ResourceData {
+        account_type: AccountType::Resource,
+        provider: *provider_account.key,
+        capacity: params.capacity,
+        capabilities: params.capabilities,
+        cpu_architecture: params.cpu_architecture,
+        gpu_model: params.gpu_model,
+        price_per_hour: params.price_per_hour,
+        availability: params.availability,
+        location_code: params.location_code,
+        zone: params.zone,
+        ..ResourceData::default()
+    };
+
+    // Save to account
+    save_account(&data, resource_account)?;
+
+    msg!("Resource registered successfully");
+    Ok(())
+}
+
+// Update a resource's published offer. Capacity cannot shrink below what is
+// already reserved by matched requests.
+fn process_update_resource(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: ResourceParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let resource_account = next_account_info(account_info_iter)?;
+    let provider_account = next_account_info(account_info_iter)?;
+
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+
+    // Only the provider updates its resource
+    if !provider_account.is_signer || resource_data.provider != *provider_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    validate_resource_params(&params)?;
+    if params.capacity.covers(&resource_data.allocated).is_err() {
+        return Err(MatchingError::InsufficientCapacity.into());
+    }
+
+    resource_data.capacity = params.capacity;
+    resource_data.capabilities = params.capabilities;
+    resource_data.cpu_architecture = params.cpu_architecture;
+    resource_data.gpu_model = params.gpu_model;
+    resource_data.price_per_hour = params.price_per_hour;
+    resource_data.availability = params.availability;
+    resource_data.location_code = params.location_code;
+    resource_data.zone = params.zone;
+
+    // Save updated resource
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Resource updated successfully");
+    Ok(())
+}
+
+// Submit a computation request in an account the requester created for this
+// program with ComputationRequest::LEN bytes
+fn process_submit_request(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: RequestParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let request_account = next_account_info(account_info_iter)?;
+    let requester_account = next_account_info(account_info_iter)?;
+
+    // Verify requester is signer
+    if !requester_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_uninitialized(program_id, request_account, ComputationRequest::LEN)?;
+
+    // Borsh decoding does not check taxonomy codes, so validate them here
+    params.requirements.validate()?;
+    RegionCode::try_from(params.preferred_location.code())?;
+    if let Some(residency) = params.residency {
+        RegionScope::try_from(residency.code())?;
+    }
+
+    let data = ComputationRequest {
+        account_type: AccountType::Request,
+        requester: *requester_account.key,
+        job_category: params.job_category,
+        cpu_architecture: params.cpu_architecture,
+        requirements: params.requirements,
+        required_capabilities: params.required_capabilities,
+        optional_capabilities: params.optional_capabilities,
+        gpu_model: params.gpu_model,
+        max_price_per_hour: params.max_price_per_hour,
+        preferred_location: params.preferred_location,
+        preferred_zone: params.preferred_zone,
+        residency: params.residency,
+        min_reputation: params.min_reputation,
+        ..ComputationRequest::default()
+    };
+
+    // Save to account
+    save_account(&data, request_account)?;
+
+    msg!("Computation request submitted successfully");
+    Ok(())
+}
+
+// Match a computation request with available resources
+fn process_match_request(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let request_account = next_account_info(account_info_iter)?;
+    let resource_account = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let matching_engine = next_account_info(account_info_iter)?;
+
+    // Unpack request, resource and config data
+    let mut request_data: ComputationRequest =
+        load_account(program_id, request_account, AccountType::Request)?;
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+    let config = load_config(program_id, config_account)?;
+
+    // The requester, the provider or the system authority decides the match
+    if !matching_engine.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *matching_engine.key != request_data.requester
+        && *matching_engine.key != resource_data.provider
+        && *matching_engine.key != config.authority
+    {
+        return Err(MatchingError::UnauthorizedMatcher.into());
+    }
+
+    // Verify request is pending
+    if request_data.status != RequestStatus::Pending {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    check_requirements(&request_data, &resource_data)?;
+
+    // Which resource a request goes to is decided off chain by the calctra-matcher
+    // crate, which weighs price, reputation, location and capacity fit across all
+    // pending requests. The program only enforces the hard constraints above.
+
+    // Update request status and reserve the requested share of the resource
+    let requirements = request_data.requirements.consumable();
+    request_data.status = RequestStatus::Matched;
+    request_data.matched_provider = Some(resource_data.provider);
+    request_data.matched_resource = Some(*resource_account.key);
+    resource_data.allocated = resource_data
+        .allocated
+        .checked_add(&requirements)
+        .ok_or(MatchingError::InsufficientCapacity)?;
+
+    // Save updated request and resource
+    save_account(&request_data, request_account)?;
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Computation request matched successfully");
+    Ok(())
+}
+
+// Complete a matched request and release its reservation on the resource.
+// The instruction before this one must verify a worker's Ed25519 signature over
+// the request, result hash and runtime.
+fn process_complete_request(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    success: bool,
+    result_hash: [u8; 32],
+    runtime: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let request_account = next_account_info(account_info_iter)?;
+    let resource_account = next_account_info(account_info_iter)?;
+    let provider_account = next_account_info(account_info_iter)?;
+    let instructions_account = next_account_info(account_info_iter)?;
+
+    if !instructions::check_id(instructions_account.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut request_data: ComputationRequest =
+        load_account(program_id, request_account, AccountType::Request)?;
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+
+    // Only the matched provider reports completion
+    if !provider_account.is_signer || resource_data.provider != *provider_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if request_data.status != RequestStatus::Matched
+        && request_data.status != RequestStatus::InProgress
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // The result must be attested by a worker key enrolled for the resource
+    let attestation = ResultAttestation {
+        request: *request_account.key,
+        result_hash,
+        runtime,
+    };
+    let worker_key = attestation
+        .verify_preceding(instructions_account)
+        .map_err(|e| {
+            msg!("Result attestation rejected: {}", e);
+            MatchingError::InvalidResultAttestation
+        })?;
+    if !resource_data.worker_keys.contains(&worker_key) {
+        return Err(MatchingError::UnknownWorkerKey.into());
+    }
+
+    release_allocation(&mut request_data, &mut resource_data, resource_account.key)?;
+    request_data.result_hash = Some(result_hash);
+    request_data.status = if success {
+        RequestStatus::Completed
+    } else {
+        RequestStatus::Failed
+    };
+
+    // Save updated request and resource
+    save_account(&request_data, request_account)?;
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Computation request completed");
+    Ok(())
+}
+
+// Cancel a request. A matched request releases its reservation on the resource.
+fn process_cancel_request(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let request_account = next_account_info(account_info_iter)?;
+    let requester_account = next_account_info(account_info_iter)?;
+
+    let mut request_data: ComputationRequest =
+        load_account(program_id, request_account, AccountType::Request)?;
+
+    // Verify the requester signed
+    if !requester_account.is_signer || request_data.requester != *requester_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    match request_data.status {
+        RequestStatus::Pending => {}
+        RequestStatus::Matched => {
+            // The matched resource account follows the requester
+            let resource_account = next_account_info(account_info_iter)?;
+            let mut resource_data: ResourceData =
+                load_account(program_id, resource_account, AccountType::Resource)?;
+            release_allocation(&mut request_data, &mut resource_data, resource_account.key)?;
+            save_account(&resource_data, resource_account)?;
+        }
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+    request_data.status = RequestStatus::Cancelled;
+
+    // Save updated request
+    save_account(&request_data, request_account)?;
+
+    msg!("Computation request cancelled");
+    Ok(())
+}
+
+// Enroll a worker key whose signatures are accepted as result attestations
+fn process_add_worker_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    worker_key: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let resource_account = next_account_info(account_info_iter)?;
+    let provider_account = next_account_info(account_info_iter)?;
+
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+
+    // Only the provider manages its worker keys
+    if !provider_account.is_signer || resource_data.provider != *provider_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !resource_data.worker_keys.contains(&worker_key) {
+        if resource_data.worker_keys.len() >= MAX_WORKER_KEYS {
+            return Err(MatchingError::TooManyWorkerKeys.into());
+        }
+        resource_data.worker_keys.push(worker_key);
+    }
+
+    // Save updated resource
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Worker key enrolled");
+    Ok(())
+}
+
+// Revoke a worker key so its attestations are rejected
+fn process_remove_worker_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    worker_key: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let resource_account = next_account_info(account_info_iter)?;
+    let provider_account = next_account_info(account_info_iter)?;
+
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+
+    // Only the provider manages its worker keys
+    if !provider_account.is_signer || resource_data.provider != *provider_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let index = resource_data
+        .worker_keys
+        .iter()
+        .position(|key| *key == worker_key)
+        .ok_or(MatchingError::WorkerKeyNotFound)?;
+    resource_data.worker_keys.remove(index);
+
+    // Save updated resource
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Worker key removed");
+    Ok(())
+}
+
+// Publish a new X25519 key for confidential job inputs, keeping the previous one
+// so inputs sealed before the rotation can still be opened
+fn process_rotate_encryption_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    encryption_key: [u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let resource_account = next_account_info(account_info_iter)?;
+    let provider_account = next_account_info(account_info_iter)?;
+
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+
+    // Only the provider rotates its keys
+    if !provider_account.is_signer || resource_data.provider != *provider_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if encryption_key == [0; 32] || encryption_key == resource_data.encryption_key {
+        return Err(MatchingError::InvalidEncryptionKey.into());
+    }
+    resource_data.previous_encryption_key = resource_data.encryption_key;
+    resource_data.encryption_key = encryption_key;
+    resource_data.encryption_key_epoch += 1;
+
+    // Save updated resource
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Encryption key rotated to epoch {}", resource_data.encryption_key_epoch);
+    Ok(())
+}
+
+// Record a provider's reputation score. Only the system authority rates providers.
+fn process_set_reputation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reputation_score: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let resource_account = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+
+    let mut resource_data: ResourceData =
+        load_account(program_id, resource_account, AccountType::Resource)?;
+    let config = load_config(program_id, config_account)?;
+
+    if !authority_account.is_signer || config.authority != *authority_account.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    resource_data.reputation_score = reputation_score;
+
+    // Save updated resource
+    save_account(&resource_data, resource_account)?;
+
+    msg!("Reputation set to {}", reputation_score);
+    Ok(())
+}
+
+// Every hard constraint a request places on the resource it is matched to
+fn check_requirements(
+    request_data: &ComputationRequest,
+    resource_data: &ResourceData,
+) -> ProgramResult {
+    if !resource_data.availability {
+        return Err(MatchingError::ResourceUnavailable.into());
+    }
+    if let Some(gpu_model) = request_data.gpu_model {
+        if resource_data.gpu_model != gpu_model {
+            return Err(MatchingError::AcceleratorMismatch.into());
+        }
+    }
+    if let Some(cpu_architecture) = request_data.cpu_architecture {
+        if resource_data.cpu_architecture != cpu_architecture {
+            return Err(MatchingError::ArchitectureMismatch.into());
+        }
+    }
+    if resource_data.price_per_hour > request_data.max_price_per_hour {
+        return Err(MatchingError::PriceTooHigh.into());
+    }
+    if resource_data.reputation_score < request_data.min_reputation {
+        return Err(MatchingError::ReputationTooLow.into());
+    }
+
+    // Every required software capability must be published by the provider
+    let missing = resource_data.capabilities.missing(request_data.required_capabilities);
+    if !missing.is_empty() {
+        msg!("Missing required capabilities: {:#034x}", missing.0);
+        return Err(MatchingError::MissingCapability.into());
+    }
+
+    // The unreserved capacity must cover every requested dimension
+    let remaining = resource_data
+        .capacity
+        .checked_sub(&resource_data.allocated)
+        .ok_or(MatchingError::InsufficientCapacity)?;
+    if let Err(dimension) = remaining.covers(&request_data.requirements.consumable()) {
+        msg!("Insufficient capacity in dimension {:?}", dimension);
+        return Err(MatchingError::InsufficientCapacity.into());
+    }
+
+    // Hard data residency constraints are never relaxed by the matching algorithm
+    if let Some(residency) = request_data.residency {
+        if !residency.contains(resource_data.location_code, resource_data.zone) {
+            return Err(MatchingError::ResidencyViolation.into());
+        }
+    }
+    Ok(())
+}
+
+// Return a request's reserved capacity to its matched resource
+fn release_allocation(
+    request_data: &mut ComputationRequest,
+    resource_data: &mut ResourceData,
+    resource_key: &Pubkey,
+) -> ProgramResult {
+    if request_data.matched_resource != Some(*resource_key) {
+        return Err(MatchingError::ResourceMismatch.into());
+    }
+    resource_data.allocated = resource_data
+        .allocated
+        .checked_sub(&request_data.requirements.consumable())
+        .ok_or(MatchingError::AllocationUnderflow)?;
+    Ok(())
+}
+
+// Borsh decoding does not check taxonomy codes, so validate them here
+fn validate_resource_params(params: &ResourceParams) -> ProgramResult {
+    params.capacity.validate()?;
+    RegionCode::try_from(params.location_code.code())?;
+    Ok(())
+}
+
+// A fresh, rent-exempt account owned by this program and large enough for `len` bytes
+fn check_uninitialized(program_id: &Pubkey, account: &AccountInfo, len: usize) -> ProgramResult {
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if account.data_len() < len {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    if !Rent::get()?.is_exempt(account.lamports(), account.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    if account.data.borrow()[0] != AccountType::Uninitialized as u8 {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+// Decode an account owned by this program, checking its account type tag
+fn load_account<T: BorshDeserialize>(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    account_type: AccountType,
+) -> Result<T, ProgramError> {
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = account.data.borrow();
+    if data.first() != Some(&(account_type as u8)) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    T::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+// Decode the config, checking that it is the PDA and not a look-alike
+fn load_config(program_id: &Pubkey, account: &AccountInfo) -> Result<Config, ProgramError> {
+    let config: Config = load_account(program_id, account, AccountType::Config)?;
+    let expected = Pubkey::create_program_address(&[CONFIG_SEED, &[config.bump]], program_id)?;
+    if *account.key != expected {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(config)
+}
+
+// Encode an account in place. Accounts are sized for their largest encoding, so
+// a shorter encoding leaves the trailing bytes untouched.
+fn save_account<T: BorshSerialize>(value: &T, account: &AccountInfo) -> ProgramResult {
+    let mut data = account.data.borrow_mut();
+    value
+        .serialize(&mut &mut data[..])
+        .map_err(|_| ProgramError::AccountDataTooSmall)
+}
+
+// Instruction types. Resource and request fields are Borsh-encoded after the tag.
+pub enum MatchingInstruction {
+    RegisterResource(ResourceParams),
+    UpdateResource(ResourceParams),
+    SubmitRequest(RequestParams),
+    MatchRequest,
+    CompleteRequest { success: bool, result_hash: [u8; 32], runtime: u64 },
+    CancelRequest,
+    AddWorkerKey { worker_key: Pubkey },
+    RemoveWorkerKey { worker_key: Pubkey },
+    RotateEncryptionKey { encryption_key: [u8; 32] },
+    InitializeConfig,
+    SetReputation { reputation_score: u8 },
+}
+
+impl MatchingInstruction {
+    // Unpacks a byte buffer into a MatchingInstruction
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(match tag {
+            0 => Self::RegisterResource(Self::unpack_borsh(rest)?),
+            1 => Self::UpdateResource(Self::unpack_borsh(rest)?),
+            2 => Self::SubmitRequest(Self::unpack_borsh(rest)?),
+            3 => Self::MatchRequest,
+            4 => {
+                let (&success, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let result_hash = rest
+                    .get(..32)
+                    .and_then(|slice| slice.try_into().ok())
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let runtime = rest
+                    .get(32..40)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                Self::CompleteRequest { success: success != 0, result_hash, runtime }
+            }
+            5 => Self::CancelRequest,
+            6 => Self::AddWorkerKey { worker_key: Self::unpack_pubkey(rest)? },
+            7 => Self::RemoveWorkerKey { worker_key: Self::unpack_pubkey(rest)? },
+            8 => Self::RotateEncryptionKey {
+                encryption_key: rest
+                    .get(..32)
+                    .and_then(|slice| slice.try_into().ok())
+                    .ok_or(ProgramError::InvalidInstructionData)?,
+            },
+            9 => Self::InitializeConfig,
+            10 => Self::SetReputation {
+                reputation_score: *rest.first().ok_or(ProgramError::InvalidInstructionData)?,
+            },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<Pubkey, ProgramError> {
+        input
+            .get(..32)
+            .and_then(|slice| Pubkey::try_from(slice).ok())
+            .ok_or(ProgramError::InvalidInstructionData)
+    }
+
+    fn unpack_borsh<T: BorshDeserialize>(input: &[u8]) -> Result<T, ProgramError> {
+        T::try_from_slice(input).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use calctra_contracts::resource_vector::{Dimension, ResourceVector};
+    use calctra_contracts::taxonomy::{AcceleratorModel, CpuArchitecture, RegionCode};
+    use calctra_resource_matching::{
+        process_instruction, AccountType, ComputationRequest, Config, MatchingError,
+        RequestStatus, ResourceData, CONFIG_SEED,
+    };
+    use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+    use solana_program_test::*;
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    };
+
+    // Change to a matching resource that breaks one of the request's requirements
+    type Mutation = fn(&mut ResourceData);
+
+    struct Fixture {
+        program_id: Pubkey,
+        request: Pubkey,
+        resource: Pubkey,
+        config: Pubkey,
+        requester: Keypair,
+        provider: Keypair,
+        authority: Keypair,
+    }
+
+    fn cores(count: u64) -> ResourceVector {
+        let mut vector = ResourceVector::default();
+        vector.set(Dimension::CpuCores, count);
+        vector
+    }
+
+    fn resource_data(provider: Pubkey) -> ResourceData {
+        ResourceData {
+            account_type: AccountType::Resource,
+            provider,
+            capacity: cores(16),
+            cpu_architecture: CpuArchitecture::X86_64,
+            gpu_model: AcceleratorModel::NvidiaA100,
+            price_per_hour: 100,
+            availability: true,
+            reputation_score: 80,
+            location_code: RegionCode(*b"DE"),
+            ..ResourceData::default()
+        }
+    }
+
+    fn request_data(requester: Pubkey) -> ComputationRequest {
+        ComputationRequest {
+            account_type: AccountType::Request,
+            requester,
+            cpu_architecture: Some(CpuArchitecture::X86_64),
+            requirements: cores(8),
+            gpu_model: Some(AcceleratorModel::NvidiaA100),
+            max_price_per_hour: 150,
+            preferred_location: RegionCode(*b"DE"),
+            min_reputation: 50,
+            status: RequestStatus::Pending,
+            ..ComputationRequest::default()
+        }
+    }
+
+    fn add_borsh_account<T: BorshSerialize>(
+        program_test: &mut ProgramTest,
+        program_id: &Pubkey,
+        address: Pubkey,
+        value: &T,
+        len: usize,
+    ) {
+        let mut data = value.try_to_vec().unwrap();
+        data.resize(len, 0);
+        program_test.add_account(
+            address,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner: *program_id,
+                ..Account::default()
+            },
+        );
+    }
+
+    fn program_test(
+        fixture: &Fixture,
+        request: &ComputationRequest,
+        resource: &ResourceData,
+    ) -> ProgramTest {
+        let mut program_test = ProgramTest::new(
+            "calctra_resource_matching",
+            fixture.program_id,
+            processor!(process_instruction),
+        );
+        let (_, bump) = Pubkey::find_program_address(&[CONFIG_SEED], &fixture.program_id);
+        let config = Config {
+            account_type: AccountType::Config,
+            authority: fixture.authority.pubkey(),
+            bump,
+        };
+        add_borsh_account(&mut program_test, &fixture.program_id, fixture.config, &config, Config::LEN);
+        add_borsh_account(
+            &mut program_test,
+            &fixture.program_id,
+            fixture.request,
+            request,
+            ComputationRequest::LEN,
+        );
+        add_borsh_account(
+            &mut program_test,
+            &fixture.program_id,
+            fixture.resource,
+            resource,
+            ResourceData::LEN,
+        );
+        program_test
+    }
+
+    fn fixture() -> Fixture {
+        let program_id = Pubkey::new_unique();
+        let (config, _) = Pubkey::find_program_address(&[CONFIG_SEED], &program_id);
+        Fixture {
+            program_id,
+            request: Pubkey::new_unique(),
+            resource: Pubkey::new_unique(),
+            config,
+            requester: Keypair::new(),
+            provider: Keypair::new(),
+            authority: Keypair::new(),
+        }
+    }
+
+    fn match_instruction(fixture: &Fixture, matcher: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: fixture.program_id,
+            accounts: vec![
+                AccountMeta::new(fixture.request, false),
+                AccountMeta::new(fixture.resource, false),
+                AccountMeta::new_readonly(fixture.config, false),
+                AccountMeta::new_readonly(*matcher, true),
+            ],
+            data: vec![3], // 3 = MatchRequest instruction
+        }
+    }
+
+    // Run a match signed by `matcher` and return the program's custom error, if any
+    async fn run_match(
+        fixture: &Fixture,
+        request: ComputationRequest,
+        resource: ResourceData,
+        matcher: &Keypair,
+    ) -> (Option<u32>, BanksClient) {
+        let (mut banks_client, payer, recent_blockhash) =
+            program_test(fixture, &request, &resource).start().await;
+        let mut transaction = Transaction::new_with_payer(
+            &[match_instruction(fixture, &matcher.pubkey())],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, matcher], recent_blockhash);
+        let error = match banks_client.process_transaction(transaction).await {
+            Ok(()) => None,
+            Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+                _,
+                InstructionError::Custom(code),
+            ))) => Some(code),
+            Err(error) => panic!("unexpected error: {:?}", error),
+        };
+        (error, banks_client)
+    }
+
+    #[tokio::test]
+    async fn test_match_by_requester_reserves_capacity() {
+        let fixture = fixture();
+        let request = request_data(fixture.requester.pubkey());
+        let resource = resource_data(fixture.provider.pubkey());
+
+        let (error, mut banks_client) = run_match(&fixture, request, resource, &fixture.requester).await;
+        assert_eq!(error, None);
+
+        let request_account = banks_client.get_account(fixture.request).await.unwrap().unwrap();
+        let request = ComputationRequest::deserialize(&mut &request_account.data[..]).unwrap();
+        assert_eq!(request.status, RequestStatus::Matched);
+        assert_eq!(request.matched_resource, Some(fixture.resource));
+
+        let resource_account = banks_client.get_account(fixture.resource).await.unwrap().unwrap();
+        let resource = ResourceData::deserialize(&mut &resource_account.data[..]).unwrap();
+        assert_eq!(resource.allocated.get(Dimension::CpuCores), 8);
+    }
+
+    #[tokio::test]
+    async fn test_match_by_provider_or_authority() {
+        let fixture = fixture();
+        for matcher in [&fixture.provider, &fixture.authority] {
+            let request = request_data(fixture.requester.pubkey());
+            let resource = resource_data(fixture.provider.pubkey());
+            let (error, _) = run_match(&fixture, request, resource, matcher).await;
+            assert_eq!(error, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_match_rejects_unrelated_signer() {
+        let fixture = fixture();
+        let request = request_data(fixture.requester.pubkey());
+        let resource = resource_data(fixture.provider.pubkey());
+
+        let (error, _) = run_match(&fixture, request, resource, &Keypair::new()).await;
+        assert_eq!(error, Some(MatchingError::UnauthorizedMatcher as u32));
+    }
+
+    #[tokio::test]
+    async fn test_match_enforces_every_requirement() {
+        let fixture = fixture();
+        let provider = fixture.provider.pubkey();
+        let cases: [(Mutation, MatchingError); 6] = [
+            (|r| r.availability = false, MatchingError::ResourceUnavailable),
+            (|r| r.gpu_model = AcceleratorModel::NvidiaL4, MatchingError::AcceleratorMismatch),
+            (|r| r.cpu_architecture = CpuArchitecture::Aarch64, MatchingError::ArchitectureMismatch),
+            (|r| r.price_per_hour = 151, MatchingError::PriceTooHigh),
+            (|r| r.reputation_score = 49, MatchingError::ReputationTooLow),
+            (|r| r.capacity = cores(4), MatchingError::InsufficientCapacity),
+        ];
+        for (mutate, expected) in cases {
+            let request = request_data(fixture.requester.pubkey());
+            let mut resource = resource_data(provider);
+            mutate(&mut resource);
+            let (error, _) = run_match(&fixture, request, resource, &fixture.requester).await;
+            assert_eq!(error, Some(expected as u32));
+        }
+    }
+}
@@ -0,0 +1,708 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    program_pack::{Pack, IsInitialized, Sealed},
+    sysvar::{rent::Rent, Sysvar},
+};
+use thiserror::Error;
+
+// Token program state
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CalToken {
+    pub is_initialized: bool,
+    pub supply: u64,
+    pub decimals: u8,
+    pub mint_authority: Pubkey,
+}
+
+// Token account state, one per holder
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TokenAccount {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub is_frozen: bool,
+}
+
+// Spending limit granted by a token account owner to a single delegate
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Allowance {
+    pub is_initialized: bool,
+    pub source: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+}
+
+// Token program errors
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum TokenError {
+    #[error("Insufficient funds")]
+    InsufficientFunds,
+    #[error("Account is frozen")]
+    AccountFrozen,
+    #[error("Account owner does not match")]
+    OwnerMismatch,
+    #[error("Account mint does not match")]
+    MintMismatch,
+    #[error("Delegate allowance exceeded")]
+    InsufficientAllowance,
+    #[error("Allowance does not belong to this delegate")]
+    DelegateMismatch,
+    #[error("Operation overflowed")]
+    Overflow,
+    #[error("Account is already initialized")]
+    AlreadyInitialized,
+}
+
+impl From<TokenError> for ProgramError {
+    fn from(e: TokenError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// Program entry point
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+// Program logic
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    msg!("CAL Token contract: process instruction");
+
+    // Parse the instruction
+    let instruction = TokenInstruction::unpack(instruction_data)?;
+
+    // Process the instruction
+    match instruction {
+        TokenInstruction::InitializeMint => {
+            msg!("Instruction: InitializeMint");
+            process_initialize_mint(program_id, accounts)
+        }
+        TokenInstruction::MintTo { amount } => {
+            msg!("Instruction: MintTo");
+            process_mint_to(program_id, accounts, amount)
+        }
+        TokenInstruction::Transfer { amount } => {
+            msg!("Instruction: Transfer");
+            process_transfer(program_id, accounts, amount)
+        }
+        TokenInstruction::InitializeAccount => {
+            msg!("Instruction: InitializeAccount");
+            process_initialize_account(program_id, accounts)
+        }
+        TokenInstruction::Approve { amount } => {
+            msg!("Instruction: Approve");
+            process_approve(program_id, accounts, amount)
+        }
+        TokenInstruction::Revoke => {
+            msg!("Instruction: Revoke");
+            process_revoke(program_id, accounts)
+        }
+        TokenInstruction::TransferFrom { amount } => {
+            msg!("Instruction: TransferFrom");
+            process_transfer_from(program_id, accounts, amount)
+        }
+        TokenInstruction::Burn { amount } => {
+            msg!("Instruction: Burn");
+            process_burn(program_id, accounts, amount)
+        }
+        TokenInstruction::FreezeAccount => {
+            msg!("Instruction: FreezeAccount");
+            process_toggle_freeze(program_id, accounts, true)
+        }
+        TokenInstruction::ThawAccount => {
+            msg!("Instruction: ThawAccount");
+            process_toggle_freeze(program_id, accounts, false)
+        }
+    }
+}
+
+// Initialize a new token mint
+fn process_initialize_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let mint_account = next_account_info(account_info_iter)?;
+    let mint_authority = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    // Verify the mint account is owned by this program
+    if mint_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // The authority must consent to holding the mint
+    if !mint_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Verify the mint is rent exempt and not yet in use, so nobody can
+    // re-initialize it and take over the mint authority
+    let rent = Rent::from_account_info(rent_account)?;
+    if !rent.is_exempt(mint_account.lamports(), mint_account.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    if CalToken::unpack_unchecked(&mint_account.data.borrow())?.is_initialized {
+        return Err(TokenError::AlreadyInitialized.into());
+    }
+
+    // Create new token with initial supply
+    let cal_token = CalToken {
+        is_initialized: true,
+        supply: 1_000_000_000, // 1 billion tokens
+        decimals: 9,
+        mint_authority: *mint_authority.key,
+    };
+
+    // Save state
+    CalToken::pack(cal_token, &mut mint_account.data.borrow_mut())?;
+
+    msg!("CAL Token initialized successfully");
+    Ok(())
+}
+
+// Initialize a token account for a holder
+fn process_initialize_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let token_account = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    // Verify ownership
+    if token_account.owner != program_id || mint_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Verify the account is rent exempt and not yet in use
+    let rent = Rent::from_account_info(rent_account)?;
+    if !rent.is_exempt(token_account.lamports(), token_account.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    let mut account_data = TokenAccount::unpack_unchecked(&token_account.data.borrow())?;
+    if account_data.is_initialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    unpack_account::<CalToken>(program_id, mint_account)?;
+
+    account_data.is_initialized = true;
+    account_data.mint = *mint_account.key;
+    account_data.owner = *owner.key;
+    account_data.amount = 0;
+    account_data.is_frozen = false;
+    TokenAccount::pack(account_data, &mut token_account.data.borrow_mut())?;
+
+    msg!("Token account initialized successfully");
+    Ok(())
+}
+
+// Mint new tokens into a token account
+fn process_mint_to(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let mint_account = next_account_info(account_info_iter)?;
+    let destination_account = next_account_info(account_info_iter)?;
+    let mint_authority = next_account_info(account_info_iter)?;
+
+    // Verify ownership
+    if mint_account.owner != program_id || destination_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Verify the mint authority signed
+    let mut mint = unpack_account::<CalToken>(program_id, mint_account)?;
+    if !mint_authority.is_signer || mint.mint_authority != *mint_authority.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut destination = unpack_account::<TokenAccount>(program_id, destination_account)?;
+    if destination.mint != *mint_account.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if destination.is_frozen {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    mint.supply = mint.supply.checked_add(amount).ok_or(TokenError::Overflow)?;
+    destination.amount = destination.amount.checked_add(amount).ok_or(TokenError::Overflow)?;
+
+    CalToken::pack(mint, &mut mint_account.data.borrow_mut())?;
+    TokenAccount::pack(destination, &mut destination_account.data.borrow_mut())?;
+
+    msg!("Minted {} tokens", amount);
+    Ok(())
+}
+
+// Transfer tokens signed by the source account owner
+fn process_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let source_account = next_account_info(account_info_iter)?;
+    let destination_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+
+    let source = unpack_account::<TokenAccount>(program_id, source_account)?;
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if source.owner != *owner.key {
+        return Err(TokenError::OwnerMismatch.into());
+    }
+
+    move_tokens(program_id, source_account, destination_account, amount)?;
+
+    msg!("Transferred {} tokens", amount);
+    Ok(())
+}
+
+// Grant a delegate the right to transfer up to `amount` from a token account
+fn process_approve(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let source_account = next_account_info(account_info_iter)?;
+    let allowance_account = next_account_info(account_info_iter)?;
+    let delegate = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+
+    // Verify ownership
+    if source_account.owner != program_id || allowance_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let source = unpack_account::<TokenAccount>(program_id, source_account)?;
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if source.owner != *owner.key {
+        return Err(TokenError::OwnerMismatch.into());
+    }
+
+    // An allowance account is bound to one (source, delegate) pair for its lifetime
+    let mut allowance = Allowance::unpack_unchecked(&allowance_account.data.borrow())?;
+    if allowance.is_initialized
+        && (allowance.source != *source_account.key || allowance.delegate != *delegate.key)
+    {
+        return Err(TokenError::DelegateMismatch.into());
+    }
+
+    allowance.is_initialized = true;
+    allowance.source = *source_account.key;
+    allowance.delegate = *delegate.key;
+    allowance.amount = amount;
+    Allowance::pack(allowance, &mut allowance_account.data.borrow_mut())?;
+
+    msg!("Approved delegate for {} tokens", amount);
+    Ok(())
+}
+
+// Revoke a delegate's allowance
+fn process_revoke(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let source_account = next_account_info(account_info_iter)?;
+    let allowance_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+
+    // Verify ownership
+    if source_account.owner != program_id || allowance_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let source = unpack_account::<TokenAccount>(program_id, source_account)?;
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if source.owner != *owner.key {
+        return Err(TokenError::OwnerMismatch.into());
+    }
+
+    let mut allowance = unpack_account::<Allowance>(program_id, allowance_account)?;
+    if allowance.source != *source_account.key {
+        return Err(TokenError::DelegateMismatch.into());
+    }
+    allowance.amount = 0;
+    Allowance::pack(allowance, &mut allowance_account.data.borrow_mut())?;
+
+    msg!("Delegate allowance revoked");
+    Ok(())
+}
+
+// Transfer tokens on the owner's behalf, spending the delegate's allowance
+fn process_transfer_from(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let source_account = next_account_info(account_info_iter)?;
+    let destination_account = next_account_info(account_info_iter)?;
+    let allowance_account = next_account_info(account_info_iter)?;
+    let delegate = next_account_info(account_info_iter)?;
+
+    if allowance_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !delegate.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Verify the allowance covers this transfer
+    let mut allowance = unpack_account::<Allowance>(program_id, allowance_account)?;
+    if allowance.source != *source_account.key || allowance.delegate != *delegate.key {
+        return Err(TokenError::DelegateMismatch.into());
+    }
+    allowance.amount = allowance
+        .amount
+        .checked_sub(amount)
+        .ok_or(TokenError::InsufficientAllowance)?;
+
+    move_tokens(program_id, source_account, destination_account, amount)?;
+    Allowance::pack(allowance, &mut allowance_account.data.borrow_mut())?;
+
+    msg!("Delegate transferred {} tokens", amount);
+    Ok(())
+}
+
+// Burn tokens from an account, reducing supply
+fn process_burn(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let source_account = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+
+    // Verify ownership
+    if source_account.owner != program_id || mint_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut source = unpack_account::<TokenAccount>(program_id, source_account)?;
+    let mut mint = unpack_account::<CalToken>(program_id, mint_account)?;
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if source.owner != *owner.key {
+        return Err(TokenError::OwnerMismatch.into());
+    }
+    if source.mint != *mint_account.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if source.is_frozen {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    source.amount = source.amount.checked_sub(amount).ok_or(TokenError::InsufficientFunds)?;
+    mint.supply = mint.supply.checked_sub(amount).ok_or(TokenError::Overflow)?;
+
+    TokenAccount::pack(source, &mut source_account.data.borrow_mut())?;
+    CalToken::pack(mint, &mut mint_account.data.borrow_mut())?;
+
+    msg!("Burned {} tokens", amount);
+    Ok(())
+}
+
+// Freeze or thaw a token account, signed by the mint authority
+fn process_toggle_freeze(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    freeze: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get accounts
+    let token_account = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    // Verify ownership
+    if token_account.owner != program_id || mint_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut account_data = unpack_account::<TokenAccount>(program_id, token_account)?;
+    let mint = unpack_account::<CalToken>(program_id, mint_account)?;
+    if account_data.mint != *mint_account.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if !authority.is_signer || mint.mint_authority != *authority.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    account_data.is_frozen = freeze;
+    TokenAccount::pack(account_data, &mut token_account.data.borrow_mut())?;
+
+    msg!("Token account {}", if freeze { "frozen" } else { "thawed" });
+    Ok(())
+}
+
+// Debit `source_account` and credit `destination_account` after the caller has
+// checked who is allowed to move the funds
+fn move_tokens(
+    program_id: &Pubkey,
+    source_account: &AccountInfo,
+    destination_account: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    // Verify ownership
+    if source_account.owner != program_id || destination_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut source = unpack_account::<TokenAccount>(program_id, source_account)?;
+    let mut destination = unpack_account::<TokenAccount>(program_id, destination_account)?;
+    if source.mint != destination.mint {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if source.is_frozen || destination.is_frozen {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    // Self-transfers leave balances unchanged
+    if source_account.key == destination_account.key {
+        return if source.amount >= amount {
+            Ok(())
+        } else {
+            Err(TokenError::InsufficientFunds.into())
+        };
+    }
+
+    source.amount = source.amount.checked_sub(amount).ok_or(TokenError::InsufficientFunds)?;
+    destination.amount = destination.amount.checked_add(amount).ok_or(TokenError::Overflow)?;
+
+    TokenAccount::pack(source, &mut source_account.data.borrow_mut())?;
+    TokenAccount::pack(destination, &mut destination_account.data.borrow_mut())?;
+    Ok(())
+}
+
+// Decode an initialized account of type `T`, checking that this program owns it
+fn unpack_account<T: Pack + IsInitialized>(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+) -> Result<T, ProgramError> {
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    T::unpack(&account.data.borrow())
+}
+
+// Account type tags stored in the first byte of every account. Zero means the
+// account is not initialized, so a mint, a token account and an allowance can
+// never be read as one another.
+const MINT_ACCOUNT_TYPE: u8 = 1;
+const TOKEN_ACCOUNT_TYPE: u8 = 2;
+const ALLOWANCE_ACCOUNT_TYPE: u8 = 3;
+
+impl Sealed for CalToken {}
+
+impl IsInitialized for CalToken {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for CalToken {
+    const LEN: usize = 1 + 8 + 1 + 32;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = pack_account_type(self.is_initialized, MINT_ACCOUNT_TYPE);
+        dst[1..9].copy_from_slice(&self.supply.to_le_bytes());
+        dst[9] = self.decimals;
+        dst[10..42].copy_from_slice(self.mint_authority.as_ref());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(CalToken {
+            is_initialized: unpack_account_type(src[0], MINT_ACCOUNT_TYPE)?,
+            supply: unpack_u64(&src[1..9])?,
+            decimals: src[9],
+            mint_authority: unpack_pubkey(&src[10..42])?,
+        })
+    }
+}
+
+impl Sealed for TokenAccount {}
+
+impl IsInitialized for TokenAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for TokenAccount {
+    const LEN: usize = 1 + 32 + 32 + 8 + 1;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = pack_account_type(self.is_initialized, TOKEN_ACCOUNT_TYPE);
+        dst[1..33].copy_from_slice(self.mint.as_ref());
+        dst[33..65].copy_from_slice(self.owner.as_ref());
+        dst[65..73].copy_from_slice(&self.amount.to_le_bytes());
+        dst[73] = self.is_frozen as u8;
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(TokenAccount {
+            is_initialized: unpack_account_type(src[0], TOKEN_ACCOUNT_TYPE)?,
+            mint: unpack_pubkey(&src[1..33])?,
+            owner: unpack_pubkey(&src[33..65])?,
+            amount: unpack_u64(&src[65..73])?,
+            is_frozen: unpack_bool(src[73])?,
+        })
+    }
+}
+
+impl Sealed for Allowance {}
+
+impl IsInitialized for Allowance {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Allowance {
+    const LEN: usize = 1 + 32 + 32 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = pack_account_type(self.is_initialized, ALLOWANCE_ACCOUNT_TYPE);
+        dst[1..33].copy_from_slice(self.source.as_ref());
+        dst[33..65].copy_from_slice(self.delegate.as_ref());
+        dst[65..73].copy_from_slice(&self.amount.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Allowance {
+            is_initialized: unpack_account_type(src[0], ALLOWANCE_ACCOUNT_TYPE)?,
+            source: unpack_pubkey(&src[1..33])?,
+            delegate: unpack_pubkey(&src[33..65])?,
+            amount: unpack_u64(&src[65..73])?,
+        })
+    }
+}
+
+fn pack_account_type(is_initialized: bool, account_type: u8) -> u8 {
+    if is_initialized {
+        account_type
+    } else {
+        0
+    }
+}
+
+fn unpack_account_type(byte: u8, account_type: u8) -> Result<bool, ProgramError> {
+    match byte {
+        0 => Ok(false),
+        byte if byte == account_type => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_bool(byte: u8) -> Result<bool, ProgramError> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_u64(bytes: &[u8]) -> Result<u64, ProgramError> {
+    bytes
+        .try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidAccountData)
+}
+
+fn unpack_pubkey(bytes: &[u8]) -> Result<Pubkey, ProgramError> {
+    Pubkey::try_from(bytes).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+// Instruction types
+pub enum TokenInstruction {
+    InitializeMint,
+    MintTo { amount: u64 },
+    Transfer { amount: u64 },
+    InitializeAccount,
+    Approve { amount: u64 },
+    Revoke,
+    TransferFrom { amount: u64 },
+    Burn { amount: u64 },
+    FreezeAccount,
+    ThawAccount,
+}
+
+impl TokenInstruction {
+    // Unpacks a byte buffer into a TokenInstruction
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(match tag {
+            0 => Self::InitializeMint,
+            1 => Self::MintTo { amount: Self::unpack_amount(rest)? },
+            2 => Self::Transfer { amount: Self::unpack_amount(rest)? },
+            3 => Self::InitializeAccount,
+            4 => Self::Approve { amount: Self::unpack_amount(rest)? },
+            5 => Self::Revoke,
+            6 => Self::TransferFrom { amount: Self::unpack_amount(rest)? },
+            7 => Self::Burn { amount: Self::unpack_amount(rest)? },
+            8 => Self::FreezeAccount,
+            9 => Self::ThawAccount,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+
+    // Reads a little-endian u64 amount from the instruction payload
+    fn unpack_amount(input: &[u8]) -> Result<u64, ProgramError> {
+        input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)
+    }
+}
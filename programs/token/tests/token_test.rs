@@ -0,0 +1,682 @@
+#[cfg(test)]
+mod tests {
+    use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+    use solana_program_test::*;
+    use solana_sdk::{
+        account::Account,
+        instruction::Instruction,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    };
+    use std::str::FromStr;
+    
+    // Import our token contract module
+    use calctra_token::{process_instruction, Allowance, CalToken, TokenAccount, TokenError};
+    
+    #[tokio::test]
+    async fn test_token_initialize() {
+        // Create program test environment with an empty, rent-exempt mint account
+        let program_id = Pubkey::from_str("TokenProgram1111111111111111111111111111111").unwrap();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+        let mint_account = Pubkey::new_unique();
+        add_empty_account(&mut program_test, &program_id, mint_account, CalToken::LEN);
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        let mint_authority = Keypair::new();
+        
+        // Create transaction to initialize the mint
+        let mut transaction = Transaction::new_with_payer(
+            &[create_initialize_mint_instruction(
+                &program_id,
+                &mint_account,
+                &mint_authority.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &mint_authority], recent_blockhash);
+        
+        // Process the transaction
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // Verify the mint was initialized correctly
+        let mint_account_data = banks_client.get_account(mint_account).await.unwrap().unwrap();
+        let cal_token = CalToken::unpack_from_slice(&mint_account_data.data).unwrap();
+        
+        assert!(cal_token.is_initialized);
+        assert_eq!(cal_token.supply, 1_000_000_000);
+        assert_eq!(cal_token.decimals, 9);
+        assert_eq!(cal_token.mint_authority, mint_authority.pubkey());
+
+        // A second initialization cannot take over the mint authority
+        let attacker = Keypair::new();
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_initialize_mint_instruction(&program_id, &mint_account, &attacker.pubkey()),
+            &[&attacker],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::AlreadyInitialized as u32));
+    }
+
+    #[tokio::test]
+    async fn test_token_initialize_requires_authority_signature_and_rent_exemption() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+        let mint_account = Pubkey::new_unique();
+        let underfunded_mint = Pubkey::new_unique();
+        add_empty_account(&mut program_test, &program_id, mint_account, CalToken::LEN);
+        program_test.add_account(
+            underfunded_mint,
+            Account {
+                lamports: 1,
+                data: vec![0; CalToken::LEN],
+                owner: program_id,
+                ..Account::default()
+            },
+        );
+        let (mut banks_client, payer, _) = program_test.start().await;
+        let mint_authority = Keypair::new();
+
+        // The authority does not sign
+        let mut instruction =
+            create_initialize_mint_instruction(&program_id, &mint_account, &mint_authority.pubkey());
+        instruction.accounts[1].is_signer = false;
+        let result = process(&mut banks_client, &payer, instruction, &[]).await;
+        assert_eq!(
+            instruction_error(result),
+            Some(InstructionError::MissingRequiredSignature)
+        );
+
+        // The mint is not rent exempt
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_initialize_mint_instruction(&program_id, &underfunded_mint, &mint_authority.pubkey()),
+            &[&mint_authority],
+        )
+        .await;
+        assert_eq!(instruction_error(result), Some(InstructionError::AccountNotRentExempt));
+    }
+
+    #[tokio::test]
+    async fn test_token_accounts_are_not_interchangeable() {
+        // A token account passed where the mint belongs is rejected
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+        let owner = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        add_mint(&mut program_test, &program_id, mint, owner.pubkey(), 1_000);
+        add_token_account(&mut program_test, &program_id, holder, mint, owner.pubkey(), 1_000);
+        let (mut banks_client, payer, _) = program_test.start().await;
+
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_burn_instruction(&program_id, &holder, &holder, &owner.pubkey(), 1),
+            &[&owner],
+        )
+        .await;
+        assert_eq!(instruction_error(result), Some(InstructionError::InvalidAccountData));
+
+        // Extra trailing bytes are rejected too
+        let mut data = vec![0; TokenAccount::LEN + 1];
+        TokenAccount {
+            is_initialized: true,
+            mint,
+            owner: owner.pubkey(),
+            amount: 1,
+            is_frozen: false,
+        }
+        .pack_into_slice(&mut data);
+        assert!(TokenAccount::unpack(&data).is_err());
+        assert!(CalToken::unpack(&data[..CalToken::LEN]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_burn() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+        let owner = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        add_mint(&mut program_test, &program_id, mint, Pubkey::new_unique(), 1_000);
+        add_token_account(&mut program_test, &program_id, holder, mint, owner.pubkey(), 400);
+        let (mut banks_client, payer, _) = program_test.start().await;
+
+        // Burning reduces both the balance and the supply
+        process(
+            &mut banks_client,
+            &payer,
+            create_burn_instruction(&program_id, &holder, &mint, &owner.pubkey(), 150),
+            &[&owner],
+        )
+        .await
+        .unwrap();
+        let holder_data = banks_client.get_account(holder).await.unwrap().unwrap();
+        let mint_data = banks_client.get_account(mint).await.unwrap().unwrap();
+        assert_eq!(TokenAccount::unpack(&holder_data.data).unwrap().amount, 250);
+        assert_eq!(CalToken::unpack(&mint_data.data).unwrap().supply, 850);
+
+        // Burning more than the balance fails
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_burn_instruction(&program_id, &holder, &mint, &owner.pubkey(), 251),
+            &[&owner],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::InsufficientFunds as u32));
+
+        // Only the owner can burn
+        let stranger = Keypair::new();
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_burn_instruction(&program_id, &holder, &mint, &stranger.pubkey(), 1),
+            &[&stranger],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::OwnerMismatch as u32));
+    }
+
+    #[tokio::test]
+    async fn test_token_freeze_and_thaw() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+        let authority = Keypair::new();
+        let owner = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        add_mint(&mut program_test, &program_id, mint, authority.pubkey(), 1_000);
+        add_token_account(&mut program_test, &program_id, source, mint, owner.pubkey(), 500);
+        add_token_account(&mut program_test, &program_id, destination, mint, Pubkey::new_unique(), 0);
+        let (mut banks_client, payer, _) = program_test.start().await;
+
+        // Only the mint authority can freeze
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_toggle_freeze_instruction(&program_id, 8, &source, &mint, &owner.pubkey()),
+            &[&owner],
+        )
+        .await;
+        assert_eq!(
+            instruction_error(result),
+            Some(InstructionError::MissingRequiredSignature)
+        );
+
+        process(
+            &mut banks_client,
+            &payer,
+            create_toggle_freeze_instruction(&program_id, 8, &source, &mint, &authority.pubkey()),
+            &[&authority],
+        )
+        .await
+        .unwrap();
+
+        // A frozen account cannot send or burn
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_transfer_instruction(&program_id, &source, &destination, &owner.pubkey(), 100),
+            &[&owner],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::AccountFrozen as u32));
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_burn_instruction(&program_id, &source, &mint, &owner.pubkey(), 100),
+            &[&owner],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::AccountFrozen as u32));
+
+        // After thawing, transfers go through again
+        process(
+            &mut banks_client,
+            &payer,
+            create_toggle_freeze_instruction(&program_id, 9, &source, &mint, &authority.pubkey()),
+            &[&authority],
+        )
+        .await
+        .unwrap();
+        process(
+            &mut banks_client,
+            &payer,
+            create_transfer_instruction(&program_id, &source, &destination, &owner.pubkey(), 100),
+            &[&owner],
+        )
+        .await
+        .unwrap();
+        let destination_data = banks_client.get_account(destination).await.unwrap().unwrap();
+        assert_eq!(TokenAccount::unpack(&destination_data.data).unwrap().amount, 100);
+    }
+
+    #[tokio::test]
+    async fn test_token_revoke() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+        let owner = Keypair::new();
+        let delegate = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let allowance = Pubkey::new_unique();
+        add_token_account(&mut program_test, &program_id, source, mint, owner.pubkey(), 1_000);
+        add_token_account(&mut program_test, &program_id, destination, mint, Pubkey::new_unique(), 0);
+        add_empty_account(&mut program_test, &program_id, allowance, Allowance::LEN);
+        let (mut banks_client, payer, _) = program_test.start().await;
+
+        process(
+            &mut banks_client,
+            &payer,
+            create_approve_instruction(
+                &program_id,
+                &source,
+                &allowance,
+                &delegate.pubkey(),
+                &owner.pubkey(),
+                300,
+            ),
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+        // Only the source owner can revoke
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_revoke_instruction(&program_id, &source, &allowance, &delegate.pubkey()),
+            &[&delegate],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::OwnerMismatch as u32));
+
+        process(
+            &mut banks_client,
+            &payer,
+            create_revoke_instruction(&program_id, &source, &allowance, &owner.pubkey()),
+            &[&owner],
+        )
+        .await
+        .unwrap();
+        let allowance_data = banks_client.get_account(allowance).await.unwrap().unwrap();
+        assert_eq!(Allowance::unpack(&allowance_data.data).unwrap().amount, 0);
+
+        // The delegate can no longer spend
+        let result = process(
+            &mut banks_client,
+            &payer,
+            create_transfer_from_instruction(
+                &program_id,
+                &source,
+                &destination,
+                &allowance,
+                &delegate.pubkey(),
+                1,
+            ),
+            &[&delegate],
+        )
+        .await;
+        assert_eq!(custom_error(result), Some(TokenError::InsufficientAllowance as u32));
+        let source_data = banks_client.get_account(source).await.unwrap().unwrap();
+        assert_eq!(TokenAccount::unpack(&source_data.data).unwrap().amount, 1_000);
+    }
+    
+    #[tokio::test]
+    async fn test_token_mint_to() {
+        // Test implementation would go here
+    }
+    
+    #[tokio::test]
+    async fn test_token_transfer() {
+        // Test implementation would go here
+    }
+    
+    #[tokio::test]
+    async fn test_token_transfer_from_within_allowance() {
+        let program_id = Pubkey::from_str("TokenProgram1111111111111111111111111111111").unwrap();
+        let mut program_test = ProgramTest::new(
+            "calctra_token",
+            program_id,
+            processor!(process_instruction),
+        );
+
+        // Pre-initialized source and destination accounts plus an empty allowance account
+        let mint = Pubkey::new_unique();
+        let owner = Keypair::new();
+        let delegate = Keypair::new();
+        let source = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let allowance = Pubkey::new_unique();
+        add_token_account(&mut program_test, &program_id, source, mint, owner.pubkey(), 1_000);
+        add_token_account(&mut program_test, &program_id, destination, mint, Pubkey::new_unique(), 0);
+        add_empty_account(&mut program_test, &program_id, allowance, Allowance::LEN);
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        // Owner approves the delegate for 300 tokens
+        let mut transaction = Transaction::new_with_payer(
+            &[create_approve_instruction(
+                &program_id,
+                &source,
+                &allowance,
+                &delegate.pubkey(),
+                &owner.pubkey(),
+                300,
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+
+        // Delegate spends 200 of the allowance
+        let mut transaction = Transaction::new_with_payer(
+            &[create_transfer_from_instruction(
+                &program_id,
+                &source,
+                &destination,
+                &allowance,
+                &delegate.pubkey(),
+                200,
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &delegate], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+
+        let source_data = banks_client.get_account(source).await.unwrap().unwrap();
+        let destination_data = banks_client.get_account(destination).await.unwrap().unwrap();
+        let allowance_data = banks_client.get_account(allowance).await.unwrap().unwrap();
+        assert_eq!(TokenAccount::unpack_from_slice(&source_data.data).unwrap().amount, 800);
+        assert_eq!(TokenAccount::unpack_from_slice(&destination_data.data).unwrap().amount, 200);
+        assert_eq!(Allowance::unpack_from_slice(&allowance_data.data).unwrap().amount, 100);
+
+        // Spending beyond the remaining allowance fails
+        let mut transaction = Transaction::new_with_payer(
+            &[create_transfer_from_instruction(
+                &program_id,
+                &source,
+                &destination,
+                &allowance,
+                &delegate.pubkey(),
+                101,
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &delegate], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    // Sign and process a single instruction with a fresh blockhash
+    async fn process(
+        banks_client: &mut BanksClient,
+        payer: &Keypair,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        transaction.sign(&all_signers, recent_blockhash);
+        banks_client.process_transaction(transaction).await
+    }
+
+    // The instruction error a failed transaction returned
+    fn instruction_error(result: Result<(), BanksClientError>) -> Option<InstructionError> {
+        match result {
+            Err(BanksClientError::TransactionError(TransactionError::InstructionError(_, error))) => {
+                Some(error)
+            }
+            _ => None,
+        }
+    }
+
+    // The program's custom error code a failed transaction returned
+    fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+        match instruction_error(result) {
+            Some(InstructionError::Custom(code)) => Some(code),
+            _ => None,
+        }
+    }
+
+    // Helper function to add a zeroed, rent-exempt program account to the test environment
+    fn add_empty_account(program_test: &mut ProgramTest, program_id: &Pubkey, address: Pubkey, len: usize) {
+        program_test.add_account(
+            address,
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![0; len],
+                owner: *program_id,
+                ..Account::default()
+            },
+        );
+    }
+
+    // Helper function to add an initialized mint to the test environment
+    fn add_mint(
+        program_test: &mut ProgramTest,
+        program_id: &Pubkey,
+        address: Pubkey,
+        mint_authority: Pubkey,
+        supply: u64,
+    ) {
+        let mut data = vec![0; CalToken::LEN];
+        CalToken {
+            is_initialized: true,
+            supply,
+            decimals: 9,
+            mint_authority,
+        }
+        .pack_into_slice(&mut data);
+        program_test.add_account(
+            address,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner: *program_id,
+                ..Account::default()
+            },
+        );
+    }
+
+    // Helper function to add an initialized token account to the test environment
+    fn add_token_account(
+        program_test: &mut ProgramTest,
+        program_id: &Pubkey,
+        address: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) {
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount {
+            is_initialized: true,
+            mint,
+            owner,
+            amount,
+            is_frozen: false,
+        }
+        .pack_into_slice(&mut data);
+        program_test.add_account(
+            address,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner: *program_id,
+                ..Account::default()
+            },
+        );
+    }
+
+    // Helper function to create an approve instruction
+    fn create_approve_instruction(
+        program_id: &Pubkey,
+        source: &Pubkey,
+        allowance: &Pubkey,
+        delegate: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> solana_sdk::instruction::Instruction {
+        let mut data = vec![4]; // 4 = Approve instruction
+        data.extend_from_slice(&amount.to_le_bytes());
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new_readonly(*source, false),
+                solana_sdk::instruction::AccountMeta::new(*allowance, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*delegate, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*owner, true),
+            ],
+            data,
+        }
+    }
+
+    // Helper function to create a delegated transfer instruction
+    fn create_transfer_from_instruction(
+        program_id: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        allowance: &Pubkey,
+        delegate: &Pubkey,
+        amount: u64,
+    ) -> solana_sdk::instruction::Instruction {
+        let mut data = vec![6]; // 6 = TransferFrom instruction
+        data.extend_from_slice(&amount.to_le_bytes());
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(*source, false),
+                solana_sdk::instruction::AccountMeta::new(*destination, false),
+                solana_sdk::instruction::AccountMeta::new(*allowance, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*delegate, true),
+            ],
+            data,
+        }
+    }
+
+    // Helper function to create a transfer instruction
+    fn create_transfer_instruction(
+        program_id: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> solana_sdk::instruction::Instruction {
+        let mut data = vec![2]; // 2 = Transfer instruction
+        data.extend_from_slice(&amount.to_le_bytes());
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(*source, false),
+                solana_sdk::instruction::AccountMeta::new(*destination, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*owner, true),
+            ],
+            data,
+        }
+    }
+
+    // Helper function to create a revoke instruction
+    fn create_revoke_instruction(
+        program_id: &Pubkey,
+        source: &Pubkey,
+        allowance: &Pubkey,
+        owner: &Pubkey,
+    ) -> solana_sdk::instruction::Instruction {
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new_readonly(*source, false),
+                solana_sdk::instruction::AccountMeta::new(*allowance, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*owner, true),
+            ],
+            data: vec![5], // 5 = Revoke instruction
+        }
+    }
+
+    // Helper function to create a burn instruction
+    fn create_burn_instruction(
+        program_id: &Pubkey,
+        source: &Pubkey,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> solana_sdk::instruction::Instruction {
+        let mut data = vec![7]; // 7 = Burn instruction
+        data.extend_from_slice(&amount.to_le_bytes());
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(*source, false),
+                solana_sdk::instruction::AccountMeta::new(*mint, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*owner, true),
+            ],
+            data,
+        }
+    }
+
+    // Helper function to create a freeze (8) or thaw (9) instruction
+    fn create_toggle_freeze_instruction(
+        program_id: &Pubkey,
+        tag: u8,
+        account: &Pubkey,
+        mint: &Pubkey,
+        authority: &Pubkey,
+    ) -> solana_sdk::instruction::Instruction {
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(*account, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*mint, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*authority, true),
+            ],
+            data: vec![tag],
+        }
+    }
+
+    // Helper function to create an initialize mint instruction
+    fn create_initialize_mint_instruction(
+        program_id: &Pubkey,
+        mint_account: &Pubkey,
+        mint_authority: &Pubkey,
+    ) -> solana_sdk::instruction::Instruction {
+        solana_sdk::instruction::Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(*mint_account, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(*mint_authority, true),
+                solana_sdk::instruction::AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false),
+            ],
+            data: vec![0], // 0 = Initialize Mint instruction
+        }
+    }
+} 
\ No newline at end of file
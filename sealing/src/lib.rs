@@ -0,0 +1,282 @@
+// Sealing of confidential job inputs for Calctra workers.
+// Providers publish X25519 keys on their resource accounts and rotate them over time.
+// A requester seals the job spec and the key for its encrypted input data to the key
+// the matched resource currently publishes, and only that worker can open it.
+//
+// Each envelope uses a fresh ephemeral X25519 key. The shared secret is expanded with
+// HKDF-SHA256 into a ChaCha20-Poly1305 key, and the request address, key epoch and
+// both public keys are bound as associated data so an envelope cannot be replayed
+// against another request or worker.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use thiserror::Error;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+const SEAL_DOMAIN: &[u8] = b"calctra-seal-1";
+const NONCE_LEN: usize = 12;
+const INPUT_KEY_LEN: usize = 32;
+
+// Serialized envelope header: epoch, recipient, ephemeral key, nonce
+const ENVELOPE_HEADER_LEN: usize = 4 + 32 + 32 + NONCE_LEN;
+
+// An encryption key as published on a resource account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublishedKey {
+    pub key: [u8; 32],
+    pub epoch: u32,
+}
+
+// A worker's X25519 key pair for one epoch
+pub struct WorkerKeyPair {
+    secret: StaticSecret,
+    epoch: u32,
+}
+
+impl WorkerKeyPair {
+    pub fn generate(epoch: u32) -> Self {
+        Self {
+            secret: StaticSecret::random_from_rng(OsRng),
+            epoch,
+        }
+    }
+
+    pub fn from_secret_bytes(secret: [u8; 32], epoch: u32) -> Self {
+        Self {
+            secret: StaticSecret::from(secret),
+            epoch,
+        }
+    }
+
+    // The key to publish on chain with rotate_encryption_key
+    pub fn published(&self) -> PublishedKey {
+        PublishedKey {
+            key: PublicKey::from(&self.secret).to_bytes(),
+            epoch: self.epoch,
+        }
+    }
+}
+
+// Keys a worker can open envelopes with. Mirrors the on-chain resource account, which
+// keeps the key replaced by the last rotation so in-flight jobs can still be opened.
+pub struct WorkerKeyRing {
+    current: WorkerKeyPair,
+    previous: Option<WorkerKeyPair>,
+}
+
+impl WorkerKeyRing {
+    pub fn new(current: WorkerKeyPair) -> Self {
+        Self {
+            current,
+            previous: None,
+        }
+    }
+
+    // Move to the next epoch. Envelopes sealed two or more rotations ago can no
+    // longer be opened.
+    pub fn rotate(&mut self, next: WorkerKeyPair) -> Result<(), SealError> {
+        if Some(next.epoch) != self.current.epoch.checked_add(1) {
+            return Err(SealError::InvalidRotation);
+        }
+        self.previous = Some(std::mem::replace(&mut self.current, next));
+        Ok(())
+    }
+
+    pub fn current(&self) -> PublishedKey {
+        self.current.published()
+    }
+
+    fn key_for(&self, epoch: u32) -> Option<&WorkerKeyPair> {
+        std::iter::once(&self.current)
+            .chain(self.previous.as_ref())
+            .find(|pair| pair.epoch == epoch)
+    }
+}
+
+// Secrets a requester hands to the matched worker
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobSecrets {
+    // Symmetric key the job's input data is encrypted with
+    pub input_key: [u8; INPUT_KEY_LEN],
+    pub job_spec: Vec<u8>,
+}
+
+impl JobSecrets {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.input_key.as_slice(), &self.job_spec].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SealError> {
+        if bytes.len() < INPUT_KEY_LEN {
+            return Err(SealError::Malformed);
+        }
+        let (input_key, job_spec) = bytes.split_at(INPUT_KEY_LEN);
+        Ok(Self {
+            input_key: input_key.try_into().map_err(|_| SealError::Malformed)?,
+            job_spec: job_spec.to_vec(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedEnvelope {
+    pub epoch: u32,
+    pub recipient: [u8; 32],
+    pub ephemeral_key: [u8; 32],
+    pub nonce: [u8; NONCE_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+impl SealedEnvelope {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            self.epoch.to_le_bytes().as_slice(),
+            &self.recipient,
+            &self.ephemeral_key,
+            &self.nonce,
+            &self.ciphertext,
+        ]
+        .concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SealError> {
+        if bytes.len() < ENVELOPE_HEADER_LEN {
+            return Err(SealError::Malformed);
+        }
+        Ok(Self {
+            epoch: u32::from_le_bytes(fixed(&bytes[0..4])),
+            recipient: fixed(&bytes[4..36]),
+            ephemeral_key: fixed(&bytes[36..68]),
+            nonce: fixed(&bytes[68..ENVELOPE_HEADER_LEN]),
+            ciphertext: bytes[ENVELOPE_HEADER_LEN..].to_vec(),
+        })
+    }
+
+    fn associated_data(&self, request: &[u8; 32]) -> Vec<u8> {
+        [
+            SEAL_DOMAIN,
+            request,
+            &self.epoch.to_le_bytes(),
+            &self.recipient,
+            &self.ephemeral_key,
+        ]
+        .concat()
+    }
+}
+
+// Seal a plaintext for the worker behind a published key. The request is the address
+// of the computation request account the job belongs to.
+pub fn seal(
+    recipient: &PublishedKey,
+    request: &[u8; 32],
+    plaintext: &[u8],
+) -> Result<SealedEnvelope, SealError> {
+    if recipient.epoch == 0 || recipient.key == [0; 32] {
+        return Err(SealError::UnpublishedKey);
+    }
+
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_key = PublicKey::from(&ephemeral).to_bytes();
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(recipient.key));
+    if !shared.was_contributory() {
+        return Err(SealError::UnpublishedKey);
+    }
+
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let mut envelope = SealedEnvelope {
+        epoch: recipient.epoch,
+        recipient: recipient.key,
+        ephemeral_key,
+        nonce,
+        ciphertext: Vec::new(),
+    };
+
+    let cipher = derive_cipher(shared.as_bytes(), &ephemeral_key, &recipient.key);
+    let aad = envelope.associated_data(request);
+    envelope.ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: &aad })
+        .map_err(|_| SealError::EncryptionFailed)?;
+    Ok(envelope)
+}
+
+// Open an envelope with whichever of the worker's keys it was sealed to
+pub fn open(
+    keys: &WorkerKeyRing,
+    request: &[u8; 32],
+    envelope: &SealedEnvelope,
+) -> Result<Vec<u8>, SealError> {
+    let pair = keys.key_for(envelope.epoch).ok_or(SealError::UnknownEpoch)?;
+    let published = pair.published();
+    if published.key != envelope.recipient {
+        return Err(SealError::RecipientMismatch);
+    }
+
+    let shared = pair.secret.diffie_hellman(&PublicKey::from(envelope.ephemeral_key));
+    let cipher = derive_cipher(shared.as_bytes(), &envelope.ephemeral_key, &published.key);
+    let aad = envelope.associated_data(request);
+    cipher
+        .decrypt(
+            Nonce::from_slice(&envelope.nonce),
+            Payload { msg: &envelope.ciphertext, aad: &aad },
+        )
+        .map_err(|_| SealError::DecryptionFailed)
+}
+
+pub fn seal_job(
+    recipient: &PublishedKey,
+    request: &[u8; 32],
+    secrets: &JobSecrets,
+) -> Result<SealedEnvelope, SealError> {
+    seal(recipient, request, &secrets.to_bytes())
+}
+
+pub fn open_job(
+    keys: &WorkerKeyRing,
+    request: &[u8; 32],
+    envelope: &SealedEnvelope,
+) -> Result<JobSecrets, SealError> {
+    JobSecrets::from_bytes(&open(keys, request, envelope)?)
+}
+
+// Copy a slice whose length the caller has already checked into an array
+fn fixed<const N: usize>(slice: &[u8]) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(slice);
+    array
+}
+
+fn derive_cipher(
+    shared: &[u8; 32],
+    ephemeral_key: &[u8; 32],
+    recipient: &[u8; 32],
+) -> ChaCha20Poly1305 {
+    let salt = [ephemeral_key.as_slice(), recipient].concat();
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(SEAL_DOMAIN, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+// Sealing errors
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum SealError {
+    #[error("Resource has not published an encryption key")]
+    UnpublishedKey,
+    #[error("Envelope was sealed to a key epoch this worker no longer holds")]
+    UnknownEpoch,
+    #[error("Envelope was sealed to a different worker key")]
+    RecipientMismatch,
+    #[error("Key rotation must advance the epoch by one")]
+    InvalidRotation,
+    #[error("Encryption failed")]
+    EncryptionFailed,
+    #[error("Envelope failed authentication")]
+    DecryptionFailed,
+    #[error("Malformed envelope")]
+    Malformed,
+}
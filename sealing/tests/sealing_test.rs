@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use calctra_sealing::{
+        open, open_job, seal, seal_job, JobSecrets, PublishedKey, SealError, SealedEnvelope,
+        WorkerKeyPair, WorkerKeyRing,
+    };
+
+    const REQUEST: [u8; 32] = [7; 32];
+
+    fn job_secrets() -> JobSecrets {
+        JobSecrets {
+            input_key: [42; 32],
+            job_spec: b"{\"image\":\"gromacs:2024\",\"steps\":500000}".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_seal_and_open_job() {
+        let keys = WorkerKeyRing::new(WorkerKeyPair::generate(1));
+
+        let envelope = seal_job(&keys.current(), &REQUEST, &job_secrets()).unwrap();
+        let envelope = SealedEnvelope::from_bytes(&envelope.to_bytes()).unwrap();
+
+        assert_eq!(open_job(&keys, &REQUEST, &envelope).unwrap(), job_secrets());
+    }
+
+    #[test]
+    fn test_open_after_key_rotation() {
+        let mut keys = WorkerKeyRing::new(WorkerKeyPair::generate(1));
+        let sealed_before_rotation = seal_job(&keys.current(), &REQUEST, &job_secrets()).unwrap();
+
+        // The key replaced by the last rotation still opens in-flight jobs
+        keys.rotate(WorkerKeyPair::generate(2)).unwrap();
+        assert_eq!(keys.current().epoch, 2);
+        assert_eq!(
+            open_job(&keys, &REQUEST, &sealed_before_rotation).unwrap(),
+            job_secrets()
+        );
+        let sealed_after_rotation = seal_job(&keys.current(), &REQUEST, &job_secrets()).unwrap();
+        assert_eq!(
+            open_job(&keys, &REQUEST, &sealed_after_rotation).unwrap(),
+            job_secrets()
+        );
+
+        // A second rotation retires the first key
+        keys.rotate(WorkerKeyPair::generate(3)).unwrap();
+        assert_eq!(
+            open_job(&keys, &REQUEST, &sealed_before_rotation),
+            Err(SealError::UnknownEpoch)
+        );
+        assert_eq!(
+            open_job(&keys, &REQUEST, &sealed_after_rotation).unwrap(),
+            job_secrets()
+        );
+    }
+
+    #[test]
+    fn test_rotation_must_advance_epoch() {
+        let mut keys = WorkerKeyRing::new(WorkerKeyPair::generate(4));
+
+        assert_eq!(keys.rotate(WorkerKeyPair::generate(4)), Err(SealError::InvalidRotation));
+        assert_eq!(keys.rotate(WorkerKeyPair::generate(6)), Err(SealError::InvalidRotation));
+        assert_eq!(keys.current().epoch, 4);
+    }
+
+    #[test]
+    fn test_open_with_mismatched_key() {
+        let intended = WorkerKeyRing::new(WorkerKeyPair::generate(1));
+        let other = WorkerKeyRing::new(WorkerKeyPair::generate(1));
+        let envelope = seal(&intended.current(), &REQUEST, b"input").unwrap();
+
+        // Same epoch, different worker
+        assert_eq!(open(&other, &REQUEST, &envelope), Err(SealError::RecipientMismatch));
+
+        // Relabelling the envelope for the other worker breaks authentication
+        let relabelled = SealedEnvelope {
+            recipient: other.current().key,
+            ..envelope.clone()
+        };
+        assert_eq!(open(&other, &REQUEST, &relabelled), Err(SealError::DecryptionFailed));
+
+        // An envelope cannot be replayed against a different request
+        assert_eq!(open(&intended, &[8; 32], &envelope), Err(SealError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_seal_to_unpublished_key() {
+        let unpublished = PublishedKey {
+            key: [0; 32],
+            epoch: 0,
+        };
+
+        assert_eq!(
+            seal(&unpublished, &REQUEST, b"input"),
+            Err(SealError::UnpublishedKey)
+        );
+    }
+}